@@ -1,14 +1,26 @@
 // This mod calculates the age of an actor, and then performs bfs on the actors divided by age brackets
+#![allow(dead_code)]
 
-use crate::csv::{ColumnVal, DataFrame}; // Your custom data structures
+use crate::csv::{ColumnVal, DataFrame, MyError, NO_DATE}; // Your custom data structures
 use crate::graph::Graph;
-use chrono::{Datelike, Utc};
-use std::collections::HashMap;
+use chrono::{Datelike, NaiveDate, Utc};
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
 
 // Calculate the actor's age
+// Birth years in or before 1900 are treated as implausible (see age_with_cutoff)
 pub fn age(data: DataFrame) -> Vec<(String, Option<ColumnVal>)> {
+    age_with_cutoff(data, 1900)
+}
+
+// Calculate the actor's age, leaving out actors born in or before min_birth_year
+// (Lower it for classic-film datasets with actors born in the 1890s)
+// Inputs: dataframe with ages (like combined.csv), the cutoff year
+// Output: a vector of (actor, age) tuples, where the age is None if it couldn't be calculated
+pub fn age_with_cutoff(data: DataFrame, min_birth_year: i64) -> Vec<(String, Option<ColumnVal>)> {
     let mut actors_and_ages = vec![];
-    let current_year = Utc::now().year();
+    let today = Utc::now().date_naive();
+    let current_year = today.year();
 
     let birth_years = data.get_column("birthYear").unwrap();
     let death_years = data.get_column("deathYear").unwrap();
@@ -29,14 +41,23 @@ pub fn age(data: DataFrame) -> Vec<(String, Option<ColumnVal>)> {
             continue;
         }
 
-        let age = match (birth_year_val, death_year_val) {
-            (ColumnVal::Two(birth), ColumnVal::Two(death)) => {
-                if *birth <= 1900 {
+        // If both ends are full dates, count whole years between them, otherwise subtract the years
+        let age = match (year_and_date(birth_year_val), year_and_date(death_year_val)) {
+            (Some((birth, birth_date)), Some((death, death_date))) => {
+                if birth <= min_birth_year {
                     None
-                } else if *death == 0 {
-                    Some(ColumnVal::Two(current_year as i64 - *birth))
+                } else if death == 0 {
+                    match birth_date {
+                        Some(birth_date) => Some(ColumnVal::Two(full_years(birth_date, today))),
+                        None => Some(ColumnVal::Two(current_year as i64 - birth)),
+                    }
                 } else if death > birth {
-                    Some(ColumnVal::Two(*death - *birth))
+                    match (birth_date, death_date) {
+                        (Some(birth_date), Some(death_date)) => {
+                            Some(ColumnVal::Two(full_years(birth_date, death_date)))
+                        }
+                        _ => Some(ColumnVal::Two(death - birth)),
+                    }
                 } else {
                     None
                 }
@@ -50,6 +71,33 @@ pub fn age(data: DataFrame) -> Vec<(String, Option<ColumnVal>)> {
     actors_and_ages
 }
 
+// Read a birth or death cell, which can be a year, a full YYYY-MM-DD date, or a date column (type 5)
+// Input: the cell
+// Output: the year (0 if the cell is empty) and the date if there is one, or None if the cell isn't either
+fn year_and_date(val: &ColumnVal) -> Option<(i64, Option<NaiveDate>)> {
+    match val {
+        ColumnVal::Two(year) => Some((*year, None)),
+        ColumnVal::Four(date) if *date == NO_DATE => Some((0, None)),
+        ColumnVal::Four(date) => Some((date.year() as i64, Some(*date))),
+        ColumnVal::One(text) => {
+            let text = text.trim();
+            if text.is_empty() {
+                Some((0, None))
+            } else if let Ok(date) = NaiveDate::parse_from_str(text, "%Y-%m-%d") {
+                Some((date.year() as i64, Some(date)))
+            } else {
+                text.parse::<i64>().ok().map(|year| (year, None))
+            }
+        }
+        _ => None,
+    }
+}
+
+// The number of whole years from one date to a later one (partial years are dropped)
+fn full_years(from: NaiveDate, to: NaiveDate) -> i64 {
+    to.years_since(from).unwrap_or(0) as i64
+}
+
 // Extract integer value from the age tuple
 // Input: Option<&(String, Option<ColumnVal>)>
 // Output: A result containing an i32 and a boxed dynamic error
@@ -68,98 +116,232 @@ fn extract_val(
     }
 }
 
-// Perform BFS grouped by age quartiles
-// Inputs: dataframe and hashmap of actors and ages
-// Outputs: actor graphs, actors BFS results, tuples containing youngest and oldest actors in each bracket
-pub fn ages_bfs(
-    data: DataFrame,
-    hash: HashMap<ColumnVal, Vec<String>>,
-) -> (
-    Graph,
-    Graph,
-    Graph,
-    Graph,
-    (Vec<(usize, usize, u32)>, u32),
-    (Vec<(usize, usize, u32)>, u32),
-    (Vec<(usize, usize, u32)>, u32),
-    (Vec<(usize, usize, u32)>, u32),
-    (i32, i32),
-    (i32, i32),
-    (i32, i32),
-    (i32, i32),
-) {
+// Extract the actor's name from the age tuple
+// Input: Option<&(String, Option<ColumnVal>)>
+// Output: the name, or an empty string if there's no actor
+fn extract_name(opt: Option<&(String, Option<ColumnVal>)>) -> String {
+    opt.map(|(name, _)| name.clone()).unwrap_or_default()
+}
+
+// Everything calculated for one age bracket
+#[derive(Debug, Clone)]
+pub struct AgeBracketResult {
+    pub graph: Graph,                         // The graph of the actors in the bracket
+    pub bfs: (Vec<(usize, usize, u32)>, f64), // The bracket's bfs distances and average
+    pub age_range: (i32, i32), // The ages of the youngest and oldest actors in the bracket
+    pub youngest_actor: String,
+    pub oldest_actor: String,
+    pub density: f64, // Fraction of the possible collaborations within the bracket that actually happened
+}
+
+// Build the graph for a bracket, run bfs on it, find its density, and find the ages and names of its youngest and oldest actors
+// Inputs: the bracket's actors (sorted by age), hashmap of all the actors and their connections
+// Output: the bracket's AgeBracketResult
+fn bracket_result(
+    group: &[(String, Option<ColumnVal>)],
+    all: &HashMap<ColumnVal, Vec<String>>,
+) -> AgeBracketResult {
+    let graph = crate::graph::hash_graph(build_connections(group, all));
+    let bfs = graph.bfs();
+    let density = graph.density();
+    AgeBracketResult {
+        graph,
+        bfs,
+        age_range: (
+            extract_val(group.first()).unwrap_or_default(), // use extract_val to turn option into i32
+            extract_val(group.last()).unwrap_or_default(),
+        ),
+        youngest_actor: extract_name(group.first()),
+        oldest_actor: extract_name(group.last()),
+        density,
+    }
+}
+
+// Builds connections for actors in each age bracket
+// Inputs: vector of actors in given age bracket, hashmap of all the actors and their ages
+// Outputs: hashmap of actors and their connections within a bracket
+fn build_connections(
+    group: &[(String, Option<ColumnVal>)],
+    all: &HashMap<ColumnVal, Vec<String>>,
+) -> HashMap<ColumnVal, Vec<String>> {
+    let mut result = HashMap::new();
+    for (actor, _) in group {
+        let key = ColumnVal::One(actor.clone());
+        if let Some(connections) = all.get(&key) {
+            result.insert(key, connections.clone());
+        }
+    }
+    result
+}
+
+// The actors with a known age, sorted from youngest to oldest
+// Input: dataframe with ages (like combined.csv)
+// Output: a vector of (actor, age) tuples
+fn sorted_ages(data: DataFrame) -> Vec<(String, Option<ColumnVal>)> {
     let mut actors_and_ages = age(data);
 
-    actors_and_ages = actors_and_ages
-        .into_iter()
-        .filter(|(_, maybe_age)| matches!(maybe_age, Some(ColumnVal::Two(val)) if *val > 0))
-        .collect();
+    actors_and_ages
+        .retain(|(_, maybe_age)| matches!(maybe_age, Some(ColumnVal::Two(val)) if *val > 0));
 
     actors_and_ages.sort_by(|a, b| match (&a.1, &b.1) {
         (Some(ColumnVal::Two(a_age)), Some(ColumnVal::Two(b_age))) => a_age.cmp(b_age),
         _ => std::cmp::Ordering::Equal,
     });
 
+    actors_and_ages
+}
+
+// Perform BFS grouped by age quartiles
+// Inputs: dataframe and hashmap of actors and ages
+// Outputs: an AgeBracketResult for each quartile, youngest first (empty if there are fewer than 4 actors with a known age)
+pub fn ages_bfs(data: DataFrame, hash: HashMap<ColumnVal, Vec<String>>) -> Vec<AgeBracketResult> {
+    ages_bfs_n(data, hash, 4)
+}
+
+// Perform BFS grouped into any number of equal-sized age brackets
+// Each bracket gets total / n_brackets actors, and the leftover actors go to the oldest bracket
+// Inputs: dataframe and hashmap of actors and ages, the number of brackets
+// Outputs: an AgeBracketResult for each bracket, youngest first
+// (empty if n_brackets is 0 or more than the number of actors with a known age, rather than brackets with no one in them)
+pub fn ages_bfs_n(
+    data: DataFrame,
+    hash: HashMap<ColumnVal, Vec<String>>,
+    n_brackets: usize,
+) -> Vec<AgeBracketResult> {
+    // An empty dataframe may not even have the age columns, so stop before looking for them
+    if data.is_empty() || n_brackets == 0 {
+        return vec![];
+    }
+    let actors_and_ages = sorted_ages(data);
+
     let total = actors_and_ages.len();
-    let q = total / 4;
-
-    let youngest = &actors_and_ages[0..q];
-    let second = &actors_and_ages[q..2 * q];
-    let third = &actors_and_ages[2 * q..3 * q];
-    let oldest = &actors_and_ages[3 * q..];
-
-
-    // Builds connections for actors in each age bracket
-    // Inputs: vector of actors in given age bracket, hashmap of all the actors and their ages
-    // Outputs: hashmap of actors and their connections within a bracket
-    fn build_connections(
-        group: &[(String, Option<ColumnVal>)],
-        all: &HashMap<ColumnVal, Vec<String>>,
-    ) -> HashMap<ColumnVal, Vec<String>> {
-        let mut result = HashMap::new();
-        for (actor, _) in group {
-            let key = ColumnVal::One(actor.clone());
-            if let Some(connections) = all.get(&key) {
-                result.insert(key, connections.clone());
-            }
+    if n_brackets > total {
+        return vec![];
+    }
+    let size = total / n_brackets;
+
+    (0..n_brackets)
+        .map(|i| {
+            let end = if i == n_brackets - 1 {
+                total
+            } else {
+                (i + 1) * size
+            };
+            bracket_result(&actors_and_ages[i * size..end], &hash)
+        })
+        .collect()
+}
+
+// Perform BFS grouped by chosen age ranges, like (0, 30) for young actors, instead of equal-sized brackets
+// Actors whose age isn't in any of the ranges are left out
+// Inputs: dataframe and hashmap of actors and ages, the (lowest, highest) age of each range (both included)
+// Outputs: a result containing an AgeBracketResult for each range (in the order given) and a boxed dynamic error
+// (if a range's lowest age is above its highest, or two ranges overlap)
+pub fn ages_bfs_ranges(
+    data: DataFrame,
+    hash: HashMap<ColumnVal, Vec<String>>,
+    ranges: &[(i64, i64)],
+) -> Result<Vec<AgeBracketResult>, Box<dyn Error>> {
+    if let Some(&(low, high)) = ranges.iter().find(|(low, high)| low > high) {
+        return Err(Box::new(MyError(format!(
+            "Age range {}-{} is backwards",
+            low, high
+        ))));
+    }
+    let mut sorted = ranges.to_vec();
+    sorted.sort();
+    for pair in sorted.windows(2) {
+        if pair[0].1 >= pair[1].0 {
+            return Err(Box::new(MyError(format!(
+                "Age ranges {}-{} and {}-{} overlap",
+                pair[0].0, pair[0].1, pair[1].0, pair[1].1
+            ))));
         }
-        result
     }
 
-    let youngest_graph = crate::graph::hash_graph(build_connections(youngest, &hash));
-    let second_graph = crate::graph::hash_graph(build_connections(second, &hash));
-    let third_graph = crate::graph::hash_graph(build_connections(third, &hash));
-    let oldest_graph = crate::graph::hash_graph(build_connections(oldest, &hash));
-
-    let youngest_bfs = youngest_graph.bfs();
-    let second_bfs = second_graph.bfs();
-    let third_bfs = third_graph.bfs();
-    let oldest_bfs = oldest_graph.bfs();
-
-    (
-        youngest_graph,
-        second_graph,
-        third_graph,
-        oldest_graph,
-        youngest_bfs,
-        second_bfs,
-        third_bfs,
-        oldest_bfs,
-        (
-            extract_val(youngest.first()).unwrap_or_default(), // for each, use extract_val to turn option into i32
-            extract_val(youngest.last()).unwrap_or_default(),
-        ),
-        (
-            extract_val(second.first()).unwrap_or_default(),
-            extract_val(second.last()).unwrap_or_default(),
-        ),
-        (
-            extract_val(third.first()).unwrap_or_default(),
-            extract_val(third.last()).unwrap_or_default(),
-        ),
-        (
-            extract_val(oldest.first()).unwrap_or_default(),
-            extract_val(oldest.last()).unwrap_or_default(),
-        ),
-    )
+    let actors_and_ages = sorted_ages(data);
+    Ok(ranges
+        .iter()
+        .map(|&(low, high)| {
+            let group: Vec<(String, Option<ColumnVal>)> = actors_and_ages
+                .iter()
+                .filter(|(_, maybe_age)| {
+                    matches!(maybe_age, Some(ColumnVal::Two(age)) if (low..=high).contains(age))
+                })
+                .cloned()
+                .collect();
+            bracket_result(&group, &hash)
+        })
+        .collect())
+}
+
+// Average distance where each actor's distances are weighted by their degree within the graph
+// (So well-connected actors count for more than actors with a single collaborator)
+// Input: a graph (designed for an age bracket's graph)
+// Output: the degree-weighted average distance (0.0 if no actor has any connections)
+pub fn degree_weighted_average(graph: &Graph) -> f64 {
+    degree_weighted_from(graph, &graph.bfs().0)
+}
+
+// Same as degree_weighted_average, but with the graph's bfs distances already worked out
+// Inputs: the graph, its bfs distances; output: the degree-weighted average distance
+fn degree_weighted_from(graph: &Graph, distances: &[(usize, usize, u32)]) -> f64 {
+    let mut weighted_total = 0.0;
+    let mut total_weight = 0.0;
+    for &(start, _, distance) in distances {
+        let degree = graph.outedges[start].len() as f64;
+        weighted_total += degree * distance as f64;
+        total_weight += degree;
+    }
+
+    if total_weight > 0.0 {
+        weighted_total / total_weight
+    } else {
+        0.0
+    }
+}
+
+// Plain and degree-weighted averages for each age bracket, youngest first
+// Inputs: dataframe and hashmap of actors and ages (same as ages_bfs)
+// Outputs: a vector with a (plain average, degree-weighted average) tuple per bracket
+pub fn ages_bfs_weighted(
+    data: DataFrame,
+    hash: HashMap<ColumnVal, Vec<String>>,
+) -> Vec<(f64, f64)> {
+    ages_bfs(data, hash)
+        .iter()
+        .map(|bracket| {
+            (
+                bracket.bfs.1,
+                degree_weighted_from(&bracket.graph, &bracket.bfs.0),
+            )
+        })
+        .collect()
+}
+
+// Find the actors whose collaborators come from the most different age brackets
+// Inputs: the actors in each bracket, the graph of all actors (with labels, like hash_graph makes), how many actors to return
+// Output: up to top (actor, number of brackets) tuples, most brackets first (ties broken by name)
+pub fn bracket_bridges(
+    brackets: &[HashSet<String>],
+    graph: &Graph,
+    top: usize,
+) -> Vec<(String, usize)> {
+    let mut spans: Vec<(String, usize)> = (0..graph.n)
+        .map(|v| {
+            let mut spanned: HashSet<usize> = HashSet::new();
+            for &u in &graph.outedges[v] {
+                for (i, bracket) in brackets.iter().enumerate() {
+                    if bracket.contains(&graph.label(u)) {
+                        spanned.insert(i);
+                    }
+                }
+            }
+            (graph.label(v), spanned.len())
+        })
+        .collect();
+
+    spans.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    spans.truncate(top);
+    spans
 }
@@ -0,0 +1,84 @@
+// This mod runs the whole analysis in one call, and collects the results so they can be saved as json
+#![allow(dead_code)]
+
+use crate::age::ages_bfs;
+use crate::csv::{ColumnVal, DataFrame};
+use crate::genre::genres_bfs;
+use crate::graph::hash_graph;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+
+// The results for one age bracket
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BracketResult {
+    pub min_age: i32,
+    pub max_age: i32,
+    pub youngest_actor: String, // The actor at the young end of the bracket
+    pub oldest_actor: String,   // The actor at the old end of the bracket
+    pub average: Option<f64>, // Average number of connections between the actors in the bracket (None if there isn't enough data)
+    pub density: f64, // Fraction of the possible collaborations within the bracket that actually happened
+}
+
+// Everything main prints, in one struct
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AnalysisResult {
+    pub overall_average: f64, // Average number of connections between all actors
+    pub brackets: Vec<BracketResult>, // Age brackets, youngest first
+    pub genres: BTreeMap<String, Option<f64>>, // Each genre and the average number of connections in it (None if there isn't enough data)
+}
+
+impl AnalysisResult {
+    // Serialize the result as json
+    // Input: self, output: a result containing the json string and a boxed dynamic error
+    pub fn to_json(&self) -> Result<String, Box<dyn Error>> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    // Read a result back from json
+    // Input: the json string, output: a result containing the AnalysisResult and a boxed dynamic error
+    pub fn from_json(json: &str) -> Result<AnalysisResult, Box<dyn Error>> {
+        Ok(serde_json::from_str(json)?)
+    }
+}
+
+// Run the overall, age bracket and genre analyses
+// Inputs: a dataframe with films and their stars (like imdb_top_1000.csv), one with ages (like combined.csv),
+// and the hashmap of all the actors and their connections (from connections on the films, so it is only built once)
+// Output: an AnalysisResult
+pub fn run_analysis(
+    films: DataFrame,
+    ages: DataFrame,
+    actors_hash: HashMap<ColumnVal, Vec<String>>,
+) -> AnalysisResult {
+    let overall_average = hash_graph(actors_hash.clone()).bfs().1;
+
+    let brackets = ages_bfs(ages, actors_hash.clone());
+    let brackets = brackets
+        .into_iter()
+        .map(|bracket| BracketResult {
+            min_age: bracket.age_range.0,
+            max_age: bracket.age_range.1,
+            youngest_actor: bracket.youngest_actor,
+            oldest_actor: bracket.oldest_actor,
+            average: bracket.graph.has_subgroup_data().then_some(bracket.bfs.1),
+            density: bracket.density,
+        })
+        .collect();
+
+    let genres = genres_bfs(films, actors_hash)
+        .into_iter()
+        .map(|(genre, genre_data)| {
+            (
+                genre,
+                genre_data.1.has_subgroup_data().then_some(genre_data.3),
+            )
+        })
+        .collect();
+
+    AnalysisResult {
+        overall_average,
+        brackets,
+        genres,
+    }
+}
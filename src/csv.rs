@@ -1,9 +1,17 @@
 // This mod allows me to process a csv as a dataframe, so that the values in it can be easily accessed
+#![allow(dead_code)]
 
+use chrono::NaiveDate;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
 use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::error::Error;
 use std::fmt;
+use std::fs::File;
 use std::hash::{Hash, Hasher};
+use std::io::Read;
 
 // Allows each entry to be sorted into a columnval, and processed accordingly
 // (Based on what that entry's type is)
@@ -11,29 +19,81 @@ use std::hash::{Hash, Hasher};
 pub enum ColumnVal {
     One(String),
     Two(i64),
-    Three(f64), // excluded from Eq/Hash
+    Three(f64), // Compared with a total order (see canonical_float)
+    Four(NaiveDate),
+    Five(bool),
 }
 
-// Custom Eq and PartialEq — exclude `Three` b/c f64
+// The date an empty or unparseable date cell is read as (like 0 for integers)
+pub const NO_DATE: NaiveDate = NaiveDate::MIN;
+
+// How many data rows read_csv_infer looks at to guess the column types
+pub const INFER_SAMPLE_ROWS: usize = 100;
+
+// Make f64s comparable and hashable: -0.0 becomes 0.0, and every NaN becomes the same (positive) NaN
+// So unlike IEEE, NaN equals NaN here (otherwise Eq and Hash wouldn't hold), and it sorts after every number
+fn canonical_float(x: f64) -> f64 {
+    if x.is_nan() {
+        f64::NAN
+    } else if x == 0.0 {
+        0.0
+    } else {
+        x
+    }
+}
+
+// Parse the run of digits at the start of a cell, ignoring whatever comes after (like " min")
+// Input: the cell, output: the number, or None if the cell doesn't start with a digit
+fn leading_integer(elem: &str) -> Option<i64> {
+    let end = elem
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(elem.len());
+    elem[..end].parse::<i64>().ok()
+}
+
+// Custom Eq and PartialEq — `Three` uses canonical_float
 impl PartialEq for ColumnVal {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (ColumnVal::One(a), ColumnVal::One(b)) => a == b,
             (ColumnVal::Two(a), ColumnVal::Two(b)) => a == b,
-            _ => false, // Three or mixed types always false
+            (ColumnVal::Three(a), ColumnVal::Three(b)) => {
+                canonical_float(*a).to_bits() == canonical_float(*b).to_bits()
+            }
+            (ColumnVal::Four(a), ColumnVal::Four(b)) => a == b,
+            (ColumnVal::Five(a), ColumnVal::Five(b)) => a == b,
+            _ => false, // Mixed types always false
         }
     }
 }
 
 impl Eq for ColumnVal {}
 
+// The position of each variant, so ColumnVals of different types still have a consistent order
+fn variant_rank(val: &ColumnVal) -> u8 {
+    match val {
+        ColumnVal::One(_) => 1,
+        ColumnVal::Two(_) => 2,
+        ColumnVal::Three(_) => 3,
+        ColumnVal::Four(_) => 4,
+        ColumnVal::Five(_) => 5,
+    }
+}
+
 // Define how to order ColumnVals
-// Currently only defined for two because that's all I needed (to sort the ages)
+// One sorts alphabetically, two (ages) and three (ratings) numerically, four by date and five false before true
+// Values of different types sort by variant, One first
 impl Ord for ColumnVal {
     fn cmp(&self, other: &Self) -> Ordering {
         match (self, other) {
+            (ColumnVal::One(a), ColumnVal::One(b)) => a.cmp(b),
             (ColumnVal::Two(a), ColumnVal::Two(b)) => a.cmp(b),
-            _ => Ordering::Equal, // Define behavior for other variants if needed
+            (ColumnVal::Three(a), ColumnVal::Three(b)) => {
+                canonical_float(*a).total_cmp(&canonical_float(*b))
+            }
+            (ColumnVal::Four(a), ColumnVal::Four(b)) => a.cmp(b),
+            (ColumnVal::Five(a), ColumnVal::Five(b)) => a.cmp(b),
+            _ => variant_rank(self).cmp(&variant_rank(other)),
         }
     }
 }
@@ -45,7 +105,7 @@ impl PartialOrd for ColumnVal {
     }
 }
 
-// Define hash for columnvals - Three is excluded b/c f64s can't be hashed
+// Define hash for columnvals - Three hashes the bits of its canonical_float, so equal values hash the same
 impl Hash for ColumnVal {
     fn hash<H: Hasher>(&self, state: &mut H) {
         match self {
@@ -57,8 +117,17 @@ impl Hash for ColumnVal {
                 2u8.hash(state);
                 n.hash(state);
             }
-            ColumnVal::Three(_) => {
-                panic!("Cannot hash ColumnVal::Three due to f64 non-hashability");
+            ColumnVal::Three(x) => {
+                3u8.hash(state);
+                canonical_float(*x).to_bits().hash(state);
+            }
+            ColumnVal::Four(date) => {
+                4u8.hash(state);
+                date.hash(state);
+            }
+            ColumnVal::Five(flag) => {
+                5u8.hash(state);
+                flag.hash(state);
             }
         }
     }
@@ -71,21 +140,84 @@ impl fmt::Display for ColumnVal {
             ColumnVal::One(val) => write!(f, "{}", val),
             ColumnVal::Two(val) => write!(f, "{}", val),
             ColumnVal::Three(val) => write!(f, "{}", val),
+            ColumnVal::Four(val) => write!(f, "{}", val),
+            ColumnVal::Five(val) => write!(f, "{}", val),
         }
     }
 }
 
+// Build ColumnVals straight from strings and numbers, e.g. ColumnVal::from("Tom") or 1990.into()
+impl From<&str> for ColumnVal {
+    fn from(val: &str) -> Self {
+        ColumnVal::One(val.to_string())
+    }
+}
+
+impl From<String> for ColumnVal {
+    fn from(val: String) -> Self {
+        ColumnVal::One(val)
+    }
+}
+
+impl From<i64> for ColumnVal {
+    fn from(val: i64) -> Self {
+        ColumnVal::Two(val)
+    }
+}
+
+impl From<f64> for ColumnVal {
+    fn from(val: f64) -> Self {
+        ColumnVal::Three(val)
+    }
+}
+
 //Create a DataFrame struct which will allow me to store my data so that it is easy to access and manipulate
 #[derive(Clone, Debug)]
 pub struct DataFrame {
     pub labels: Vec<String>,
     pub table: Vec<Vec<ColumnVal>>,
     pub types: Vec<u32>,
+    pub coercions: Vec<usize>, // Per column, how many cells read_csv couldn't parse and replaced with 0
+    pub length_mismatches: usize, // Rows read_csv dropped because they had more or fewer fields than types
+    pub parse_failures: usize, // Rows read_csv dropped because a line or an f64 cell couldn't be read
+}
+
+// What read_csv_report kept and dropped
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReadReport {
+    pub rows_kept: usize,
+    pub rows_skipped: usize,
+    pub skipped_rows: Vec<usize>, // The first few skipped rows (0 is the first row after the header)
+}
+
+// How many skipped row indices a ReadReport holds on to
+pub const REPORTED_SKIPS: usize = 5;
+
+impl ReadReport {
+    // Count a skipped row, and remember its index if there's still room
+    fn skip(&mut self, index: usize) {
+        self.rows_skipped += 1;
+        if self.skipped_rows.len() < REPORTED_SKIPS {
+            self.skipped_rows.push(index);
+        }
+    }
+}
+
+// The aggregations DataFrame::aggregate can compute over a numeric column
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AggOp {
+    Sum,
+    Mean,
+    Min,
+    Max,
+    Count,
+    Std,    // Population standard deviation
+    Median, // The middle value, or the mean of the two middle values
 }
 
 // For returning errors
 #[derive(Clone, Debug)]
-pub struct MyError(String);
+pub struct MyError(pub String);
 
 // Define how errors will be displayed
 impl fmt::Display for MyError {
@@ -103,36 +235,253 @@ impl DataFrame {
             table: Vec::new(),
             labels: Vec::new(),
             types: Vec::new(),
+            coercions: Vec::new(),
+            length_mismatches: 0,
+            parse_failures: 0,
+        }
+    }
+
+    // The size of the dataframe
+    // Input: self, output: (number of rows, number of columns)
+    pub fn shape(&self) -> (usize, usize) {
+        (self.table.len(), self.labels.len())
+    }
+
+    // Whether the dataframe has no rows (it can still have labels)
+    // Input: self, output: a bool
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+
+    // Build a dataframe from columns instead of a csv (handy for tests and synthetic data)
+    // Inputs: the labels, a vector of values for each column, and the type of each column
+    // Output: a result containing the dataframe and a boxed dynamic error
+    // (Errors if there isn't one label and type per column, or if the columns have different lengths)
+    pub fn from_columns(
+        labels: Vec<String>,
+        columns: Vec<Vec<ColumnVal>>,
+        types: Vec<u32>,
+    ) -> Result<DataFrame, Box<dyn Error>> {
+        if labels.len() != columns.len() || types.len() != columns.len() {
+            return Err(Box::new(MyError(format!(
+                "Got {} columns, {} labels and {} types",
+                columns.len(),
+                labels.len(),
+                types.len()
+            ))));
+        }
+        let n_rows = columns.first().map_or(0, |c| c.len());
+        if let Some(i) = columns.iter().position(|c| c.len() != n_rows) {
+            return Err(Box::new(MyError(format!(
+                "Column {} has {} values, expected {}",
+                labels[i],
+                columns[i].len(),
+                n_rows
+            ))));
         }
+
+        // Transpose the columns into rows
+        let table = (0..n_rows)
+            .map(|r| columns.iter().map(|c| c[r].clone()).collect())
+            .collect();
+
+        Ok(DataFrame {
+            coercions: vec![0; types.len()],
+            length_mismatches: 0,
+            parse_failures: 0,
+            labels,
+            table,
+            types,
+        })
+    }
+
+    // Same as read_csv, but guess each column's type from the first INFER_SAMPLE_ROWS rows instead of passing them in
+    // A column is type 2 if every value parses as an integer, type 3 if every value parses as a float, and type 1 otherwise
+    // Empty cells don't count either way, except that a float column with empty cells is read as type 1,
+    // since read_csv skips rows with an empty type 3 cell (an integer column reads them as 0, like death years)
+    // Inputs: self, a path as a string
+    // Outputs a result containing a boxed dynamic error
+    pub fn read_csv_infer(&mut self, path: &str) -> Result<(), Box<dyn Error>> {
+        let mut rdr = csv::ReaderBuilder::new()
+            .has_headers(true)
+            .flexible(true)
+            .from_path(path)?;
+        let n_columns = rdr.headers()?.len();
+
+        // For each column: are all the non-empty values integers, are they all floats, and were there empty cells
+        let mut all_ints = vec![true; n_columns];
+        let mut all_floats = vec![true; n_columns];
+        let mut any_empty = vec![false; n_columns];
+        let mut any_values = vec![false; n_columns];
+        for record in rdr.records().take(INFER_SAMPLE_ROWS) {
+            let record = match record {
+                Ok(record) if record.len() == n_columns => record,
+                _ => continue,
+            };
+            for (i, elem) in record.iter().enumerate() {
+                let elem = elem.trim();
+                if elem.is_empty() {
+                    any_empty[i] = true;
+                    continue;
+                }
+                any_values[i] = true;
+                all_ints[i] &= elem.parse::<i64>().is_ok();
+                all_floats[i] &= elem.parse::<f64>().is_ok();
+            }
+        }
+
+        let types: Vec<u32> = (0..n_columns)
+            .map(|i| {
+                if !any_values[i] {
+                    1
+                } else if all_ints[i] {
+                    2
+                } else if all_floats[i] && !any_empty[i] {
+                    3
+                } else {
+                    1
+                }
+            })
+            .collect();
+        self.read_csv(path, &types)
     }
 
     // Takes as input self, a path as a string, and a vector of types
     // Outputs a result containing a boxed dynamic error
     pub fn read_csv(&mut self, path: &str, types: &[u32]) -> Result<(), Box<dyn Error>> {
+        self.read_delimited(path, types, b',')
+    }
+
+    // Same as read_csv, but for files split by something other than commas (like b'\t' for IMDB's .tsv files)
+    // Inputs: self, a path as a string, a vector of types, and the delimiter byte
+    // Outputs: a result containing a boxed dynamic error
+    pub fn read_delimited(
+        &mut self,
+        path: &str,
+        types: &[u32],
+        delimiter: u8,
+    ) -> Result<(), Box<dyn Error>> {
+        self.read_delimited_with_null(path, types, delimiter, "")
+    }
+
+    // Same as read_delimited, but cells equal to null_token are read as if they were empty
+    // (IMDB's own files write missing values as \N, which would otherwise be a bad integer)
+    // Inputs: self, a path as a string, a vector of types, the delimiter byte, and the null token
+    // Outputs: a result containing a boxed dynamic error
+    pub fn read_delimited_with_null(
+        &mut self,
+        path: &str,
+        types: &[u32],
+        delimiter: u8,
+        null_token: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        self.read_records(File::open(path)?, types, delimiter, null_token)?;
+        Ok(())
+    }
+
+    // Same as read_csv, but also says how many rows were kept and skipped, and which ones were skipped
+    // Inputs: self, a path as a string, and a vector of types
+    // Outputs: a result containing a ReadReport and a boxed dynamic error
+    pub fn read_csv_report(
+        &mut self,
+        path: &str,
+        types: &[u32],
+    ) -> Result<ReadReport, Box<dyn Error>> {
+        self.read_csv_report_from_reader(File::open(path)?, types)
+    }
+
+    // Same as read_csv, but reads from anything readable (a &[u8], a Cursor, stdin...) instead of a path
+    // Inputs: self, the reader, and a vector of types
+    // Outputs: a result containing a boxed dynamic error
+    pub fn read_csv_from_reader<R: Read>(
+        &mut self,
+        rdr: R,
+        types: &[u32],
+    ) -> Result<(), Box<dyn Error>> {
+        self.read_csv_report_from_reader(rdr, types)?;
+        Ok(())
+    }
+
+    // Same as read_csv_report, but reads from anything readable
+    // Inputs: self, the reader, and a vector of types
+    // Outputs: a result containing a ReadReport and a boxed dynamic error
+    pub fn read_csv_report_from_reader<R: Read>(
+        &mut self,
+        rdr: R,
+        types: &[u32],
+    ) -> Result<ReadReport, Box<dyn Error>> {
+        self.read_records(rdr, types, b',', "")
+    }
+
+    // Where all the readers above end up: parse the header and rows split by delimiter, according to types
+    // Inputs: self, the reader, a vector of types, the delimiter byte, and the null token ("" for none)
+    // Outputs: a result containing a ReadReport and a boxed dynamic error
+    fn read_records<R: Read>(
+        &mut self,
+        rdr: R,
+        types: &[u32],
+        delimiter: u8,
+        null_token: &str,
+    ) -> Result<ReadReport, Box<dyn Error>> {
         let mut rdr = csv::ReaderBuilder::new()
-            .delimiter(b',') // Commas are the delimiter
+            .delimiter(delimiter)
             .has_headers(true) // The CSV has headers, so we will read them
             .quote(b'"') // Ensures quoted fields are handled correctly
             .flexible(true)
-            .from_path(path)?;
+            .from_reader(rdr);
 
         self.types = types.to_vec();
+        self.coercions = vec![0; types.len()];
+        self.length_mismatches = 0;
+        self.parse_failures = 0;
 
         // Read column labels (headers)
         self.labels = rdr.headers()?.iter().map(|s| s.to_string()).collect();
 
+        // If there isn't one type per column, every row would be skipped below, so fail loudly instead
+        if types.len() != self.labels.len() {
+            return Err(Box::new(MyError(format!(
+                "expected {} types, got {}",
+                self.labels.len(),
+                types.len()
+            ))));
+        }
+
+        let mut report = ReadReport {
+            rows_kept: 0,
+            rows_skipped: 0,
+            skipped_rows: vec![],
+        };
+
         // If there's an error reading a line, skip it
-        for result in rdr.records() {
+        for (index, result) in rdr.records().enumerate() {
             let r = match result {
                 Ok(record) => record,
-                Err(_) => continue,
+                Err(_) => {
+                    self.parse_failures += 1;
+                    report.skip(index);
+                    continue;
+                }
             };
 
-            // Create an empty row
+            // Flexible mode lets rows with extra or missing fields through, so skip those here
+            if r.len() != types.len() {
+                self.length_mismatches += 1;
+                report.skip(index);
+                continue;
+            }
+
+            // Create an empty row, and keep track of which of its cells had to be replaced with 0
             let mut row: Vec<ColumnVal> = vec![];
+            let mut coerced: Vec<usize> = vec![];
 
             // Based on the value in types, process each value in row as the appropriate ColumnVal
             for (i, elem) in r.iter().enumerate() {
+                // Remove surrounding whitespace, including a stray "\r" from Windows line endings
+                // (Otherwise "Tom Hanks\r" and "Tom Hanks" would end up as different actors)
+                let elem = elem.trim();
+                // A null token means the value is missing, so treat it like an empty cell
+                let elem = if elem == null_token { "" } else { elem };
                 let cell = match types[i] {
                     // Convert the value to a string
                     1 => ColumnVal::One(elem.to_string()),
@@ -144,7 +493,10 @@ impl DataFrame {
                         } else {
                             match elem.parse::<i64>() {
                                 Ok(parsed) => ColumnVal::Two(parsed),
-                                Err(_) => ColumnVal::Two(0),
+                                Err(_) => {
+                                    coerced.push(i);
+                                    ColumnVal::Two(0)
+                                }
                             }
                         }
                     }
@@ -160,18 +512,61 @@ impl DataFrame {
                     }
 
                     // Allows me to process runtime, turn it into ColumnVal of type Two
-                    // Removes the last four characters and then parses the rest as i64
-                    // If there's an error, replace the value with 0
+                    // Keeps the digits at the start, so "142 min", "142min" and "142" are all 142
+                    // If there aren't any, replace the value with 0
                     4 => {
                         if elem.trim().is_empty() {
                             ColumnVal::Two(0)
                         } else {
-                            match elem[..elem.len().saturating_sub(4)].parse::<i64>() {
+                            match leading_integer(elem) {
+                                Some(parsed) => ColumnVal::Two(parsed),
+                                None => {
+                                    coerced.push(i);
+                                    ColumnVal::Two(0)
+                                }
+                            }
+                        }
+                    }
+                    // Parse the value as a YYYY-MM-DD date, or else replace it with NO_DATE
+                    // (Like type 2, so living actors without a death date aren't skipped)
+                    5 => {
+                        if elem.is_empty() {
+                            ColumnVal::Four(NO_DATE)
+                        } else {
+                            match NaiveDate::parse_from_str(elem, "%Y-%m-%d") {
+                                Ok(parsed) => ColumnVal::Four(parsed),
+                                Err(_) => {
+                                    coerced.push(i);
+                                    ColumnVal::Four(NO_DATE)
+                                }
+                            }
+                        }
+                    }
+                    // Parse an amount like "28,341,469" or "$1,000" as an integer, or else replace it with 0
+                    // (For columns like Gross, where plain type 2 can't handle the commas)
+                    7 => {
+                        let amount = elem.trim_start_matches('$').trim().replace(',', "");
+                        if amount.is_empty() {
+                            ColumnVal::Two(0)
+                        } else {
+                            match amount.parse::<i64>() {
                                 Ok(parsed) => ColumnVal::Two(parsed),
-                                Err(_) => ColumnVal::Two(0),
+                                Err(_) => {
+                                    coerced.push(i);
+                                    ColumnVal::Two(0)
+                                }
                             }
                         }
                     }
+                    // Parse true/false, 1/0 or yes/no (in any case) as a bool, or else replace it with false
+                    6 => match elem.to_lowercase().as_str() {
+                        "true" | "1" | "yes" => ColumnVal::Five(true),
+                        "false" | "0" | "no" | "" => ColumnVal::Five(false),
+                        _ => {
+                            coerced.push(i);
+                            ColumnVal::Five(false)
+                        }
+                    },
                     _ => continue,
                 };
                 // Add the processed value to the row vector
@@ -181,8 +576,32 @@ impl DataFrame {
             //If no values in the row were skipped, push the row to self.table
             if row.len() == types.len() {
                 self.table.push(row);
+                report.rows_kept += 1;
+                for i in coerced {
+                    self.coercions[i] += 1;
+                }
+            } else {
+                self.parse_failures += 1;
+                report.skip(index);
             }
         }
+        Ok(report)
+    }
+
+    // Write the dataframe to a csv, with the labels as the header
+    // Fields with commas or quotes are quoted, and NO_DATE is written as an empty cell (so it reads back the same)
+    // Inputs: self, path
+    // Outputs: A result containing a boxed dynamic error
+    pub fn write_csv(&self, path: &str) -> Result<(), Box<dyn Error>> {
+        let mut wtr = csv::Writer::from_path(path)?;
+        wtr.write_record(&self.labels)?;
+        for row in &self.table {
+            wtr.write_record(row.iter().map(|val| match val {
+                ColumnVal::Four(date) if *date == NO_DATE => String::new(),
+                _ => val.to_string(),
+            }))?;
+        }
+        wtr.flush()?;
         Ok(())
     }
 
@@ -190,11 +609,303 @@ impl DataFrame {
     // Inputs: self and the column label (as a string)
     // Output: a result that contains a vector of that column's values and a boxed dynamic error
     pub fn get_column(&self, label: &str) -> Result<Vec<ColumnVal>, Box<dyn Error>> {
-        let index = self
+        let index = self.column_index(label)?;
+        self.get_column_by_index(index)
+    }
+
+    // Find the position of a column from its name
+    // Inputs: self and the column label
+    // Output: a result containing the index and a boxed dynamic error (if there's no such column)
+    fn column_index(&self, label: &str) -> Result<usize, Box<dyn Error>> {
+        Ok(self
             .labels
             .iter()
             .position(|x| x == label)
-            .ok_or_else(|| MyError(format!("Label {} not found", label)))?;
-        Ok(self.table.iter().map(|row| row[index].clone()).collect())
+            .ok_or_else(|| MyError(format!("Label {} not found", label)))?)
+    }
+
+    // Get the values in a column from its position (for files with duplicate or blank headers)
+    // Inputs: self and the column index
+    // Output: a result that contains a vector of that column's values and a boxed dynamic error (if the index is out of range)
+    pub fn get_column_by_index(&self, idx: usize) -> Result<Vec<ColumnVal>, Box<dyn Error>> {
+        if idx >= self.labels.len() {
+            return Err(Box::new(MyError(format!(
+                "Column {} is out of range, there are {} columns",
+                idx,
+                self.labels.len()
+            ))));
+        }
+        Ok(self.table.iter().map(|row| row[idx].clone()).collect())
+    }
+
+    // Report which columns had cells that read_csv couldn't parse and replaced with 0 (or NO_DATE for dates, false for bools)
+    // (Empty cells aren't counted, since those are expected, e.g. death years of living actors)
+    // Input: self
+    // Output: a hashmap from column index to the number of coerced cells, for the columns that had any
+    pub fn coercion_counts(&self) -> HashMap<usize, usize> {
+        self.coercions
+            .iter()
+            .enumerate()
+            .filter(|(_, &count)| count > 0)
+            .map(|(i, &count)| (i, count))
+            .collect()
+    }
+
+    // Aggregate a numeric column into a single value
+    // Non-numeric cells are skipped, and so are integer cells of 0, which is how read_csv stores a missing value
+    // (Otherwise the death years of living actors would pull the mean and min down to 0)
+    // Inputs: self, the column label, and the aggregation to compute
+    // Output: a result containing the value and a boxed dynamic error
+    // (Errors if the column has no numeric cells at all, or if it has no values and the aggregation needs at least one)
+    pub fn aggregate(&self, label: &str, op: AggOp) -> Result<f64, Box<dyn Error>> {
+        let column = self.get_column(label)?;
+        if !column.is_empty()
+            && !column
+                .iter()
+                .any(|val| matches!(val, ColumnVal::Two(_) | ColumnVal::Three(_)))
+        {
+            return Err(Box::new(MyError(format!(
+                "Column {} is not numeric",
+                label
+            ))));
+        }
+
+        let mut values: Vec<f64> = column
+            .into_iter()
+            .filter_map(|val| match val {
+                ColumnVal::Two(0) => None,
+                ColumnVal::Two(n) => Some(n as f64),
+                ColumnVal::Three(x) => Some(x),
+                ColumnVal::One(_) | ColumnVal::Four(_) | ColumnVal::Five(_) => None,
+            })
+            .collect();
+
+        let n = values.len() as f64;
+        if values.is_empty() && !matches!(op, AggOp::Sum | AggOp::Count) {
+            return Err(Box::new(MyError(format!("Column {} has no values", label))));
+        }
+
+        let result = match op {
+            AggOp::Sum => values.iter().sum(),
+            AggOp::Mean => values.iter().sum::<f64>() / n,
+            AggOp::Min => values.iter().copied().fold(f64::INFINITY, f64::min),
+            AggOp::Max => values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            AggOp::Count => n,
+            AggOp::Std => {
+                let mean = values.iter().sum::<f64>() / n;
+                (values.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n).sqrt()
+            }
+            AggOp::Median => {
+                values.sort_by(|a, b| a.total_cmp(b));
+                let middle = values.len() / 2;
+                if values.len().is_multiple_of(2) {
+                    (values[middle - 1] + values[middle]) / 2.0
+                } else {
+                    values[middle]
+                }
+            }
+        };
+        Ok(result)
+    }
+
+    // Shortcuts for aggregate, e.g. df.mean("IMDB_Rating")
+    // Inputs: self and the column label
+    // Output: a result containing the value and a boxed dynamic error (if the column is missing, not numeric or empty)
+    pub fn mean(&self, label: &str) -> Result<f64, Box<dyn Error>> {
+        self.aggregate(label, AggOp::Mean)
+    }
+
+    pub fn median(&self, label: &str) -> Result<f64, Box<dyn Error>> {
+        self.aggregate(label, AggOp::Median)
+    }
+
+    pub fn min(&self, label: &str) -> Result<f64, Box<dyn Error>> {
+        self.aggregate(label, AggOp::Min)
+    }
+
+    pub fn max(&self, label: &str) -> Result<f64, Box<dyn Error>> {
+        self.aggregate(label, AggOp::Max)
+    }
+
+    pub fn sum(&self, label: &str) -> Result<f64, Box<dyn Error>> {
+        self.aggregate(label, AggOp::Sum)
+    }
+
+    // Count how often each pair of values appears together in two columns (a contingency table)
+    // Inputs: self, the labels of the two columns
+    // Output: a result containing a hashmap from (row value, column value) to count, and a boxed dynamic error
+    pub fn crosstab(
+        &self,
+        row_label: &str,
+        col_label: &str,
+    ) -> Result<HashMap<(ColumnVal, ColumnVal), usize>, Box<dyn Error>> {
+        let row_vals = self.get_column(row_label)?;
+        let col_vals = self.get_column(col_label)?;
+
+        let mut counts = HashMap::new();
+        for pair in row_vals.into_iter().zip(col_vals) {
+            *counts.entry(pair).or_default() += 1;
+        }
+        Ok(counts)
+    }
+
+    // Split the dataframe into a train and a test dataframe, shuffling the rows with a seeded rng
+    // Inputs: self, the fraction of rows to put in the test set (clamped to [0, 1]), and a seed
+    // Outputs: a (train, test) tuple, both keeping self's labels and types
+    pub fn train_test_split(&self, test_fraction: f64, seed: u64) -> (DataFrame, DataFrame) {
+        let mut indices: Vec<usize> = (0..self.table.len()).collect();
+        let mut rng = StdRng::seed_from_u64(seed);
+        indices.shuffle(&mut rng);
+
+        let n_test = (self.table.len() as f64 * test_fraction.clamp(0.0, 1.0)).round() as usize;
+        let (test_indices, train_indices) = indices.split_at(n_test);
+
+        // Build a dataframe with the same labels and types out of a subset of rows
+        let subset = |rows: &[usize]| DataFrame {
+            labels: self.labels.clone(),
+            table: rows.iter().map(|&i| self.table[i].clone()).collect(),
+            types: self.types.clone(),
+            coercions: self.coercions.clone(),
+            length_mismatches: self.length_mismatches,
+            parse_failures: self.parse_failures,
+        };
+
+        (subset(train_indices), subset(test_indices))
+    }
+
+    // Keep only the rows where pred is true
+    // Inputs: self, a function that takes a row and returns whether to keep it
+    // Output: a new dataframe with the same labels and types, and the kept rows in their original order
+    pub fn filter<F: Fn(&[ColumnVal]) -> bool>(&self, pred: F) -> DataFrame {
+        DataFrame {
+            labels: self.labels.clone(),
+            table: self.table.iter().filter(|row| pred(row)).cloned().collect(),
+            types: self.types.clone(),
+            coercions: self.coercions.clone(),
+            length_mismatches: self.length_mismatches,
+            parse_failures: self.parse_failures,
+        }
+    }
+
+    // Keep only the named columns, in the order they're asked for
+    // Inputs: self, the labels of the columns to keep
+    // Output: a result containing the new dataframe and a boxed dynamic error (listing any labels that weren't found)
+    pub fn select(&self, labels: &[&str]) -> Result<DataFrame, Box<dyn Error>> {
+        let positions: Vec<Option<usize>> = labels
+            .iter()
+            .map(|label| self.labels.iter().position(|x| x == label))
+            .collect();
+        let missing: Vec<&str> = labels
+            .iter()
+            .zip(&positions)
+            .filter(|(_, position)| position.is_none())
+            .map(|(&label, _)| label)
+            .collect();
+        if !missing.is_empty() {
+            return Err(Box::new(MyError(format!(
+                "Labels {} not found",
+                missing.join(", ")
+            ))));
+        }
+
+        let indices: Vec<usize> = positions.into_iter().flatten().collect();
+        Ok(DataFrame {
+            labels: indices.iter().map(|&i| self.labels[i].clone()).collect(),
+            table: self
+                .table
+                .iter()
+                .map(|row| indices.iter().map(|&i| row[i].clone()).collect())
+                .collect(),
+            types: indices.iter().map(|&i| self.types[i]).collect(),
+            coercions: indices
+                .iter()
+                .map(|&i| self.coercions.get(i).copied().unwrap_or(0))
+                .collect(),
+            length_mismatches: self.length_mismatches,
+            parse_failures: self.parse_failures,
+        })
+    }
+
+    // Split the rows into one dataframe per distinct value in a column (compared as strings)
+    // Inputs: self and the column label
+    // Output: a result containing a hashmap of value to the dataframe of its rows (same labels and types, rows in their original order)
+    // and a boxed dynamic error (if there's no such column)
+    pub fn group_by(&self, label: &str) -> Result<HashMap<String, DataFrame>, Box<dyn Error>> {
+        let index = self.column_index(label)?;
+        let mut groups: HashMap<String, DataFrame> = HashMap::new();
+        for row in &self.table {
+            groups
+                .entry(row[index].to_string())
+                .or_insert_with(|| DataFrame {
+                    labels: self.labels.clone(),
+                    table: vec![],
+                    types: self.types.clone(),
+                    coercions: self.coercions.clone(),
+                    length_mismatches: self.length_mismatches,
+                    parse_failures: self.parse_failures,
+                })
+                .table
+                .push(row.clone());
+        }
+        Ok(groups)
+    }
+
+    // Sort the rows by the values in one column (ties keep their original order)
+    // Inputs: self, the column label, and whether to sort smallest first
+    // Output: a result containing a boxed dynamic error (if there's no such column)
+    pub fn sort_by_column(&mut self, label: &str, ascending: bool) -> Result<(), Box<dyn Error>> {
+        let index = self.column_index(label)?;
+        if ascending {
+            self.table.sort_by(|a, b| a[index].cmp(&b[index]));
+        } else {
+            self.table.sort_by(|a, b| b[index].cmp(&a[index]));
+        }
+        Ok(())
+    }
+
+    // Bin a numeric column into labeled categories, and add them to the dataframe as a new string column
+    // A value below edges[0] gets bin_labels[0], a value in [edges[i-1], edges[i]) gets bin_labels[i],
+    // and a value at or above the last edge gets the last label
+    // Inputs: self, the label of the numeric column, the bin edges (ascending), and a label for each bin
+    // Output: a result containing a boxed dynamic error
+    pub fn bin_column(
+        &mut self,
+        label: &str,
+        edges: &[f64],
+        bin_labels: &[&str],
+    ) -> Result<(), Box<dyn Error>> {
+        if bin_labels.len() != edges.len() + 1 {
+            return Err(Box::new(MyError(format!(
+                "Expected {} bin labels for {} edges, got {}",
+                edges.len() + 1,
+                edges.len(),
+                bin_labels.len()
+            ))));
+        }
+
+        // Look up the bin of each value before changing anything, so a bad value leaves self untouched
+        let mut bins = vec![];
+        for val in self.get_column(label)? {
+            let value = match val {
+                ColumnVal::Two(n) => n as f64,
+                ColumnVal::Three(x) => x,
+                ColumnVal::One(_) | ColumnVal::Four(_) | ColumnVal::Five(_) => {
+                    return Err(Box::new(MyError(format!(
+                        "Column {} is not numeric",
+                        label
+                    ))))
+                }
+            };
+            let bin = edges.iter().take_while(|&&edge| value >= edge).count();
+            bins.push(bin_labels[bin].to_string());
+        }
+
+        self.labels.push(format!("{}_bin", label));
+        self.types.push(1);
+        self.coercions.push(0);
+        for (row, bin) in self.table.iter_mut().zip(bins) {
+            row.push(ColumnVal::One(bin));
+        }
+        Ok(())
     }
 }
@@ -1,5 +1,7 @@
 // This mod allows me to sort the actors by genre, and then perform a bfs on the actors in a given genre
+#![allow(dead_code)]
 
+use crate::age::age;
 use crate::csv::*;
 use crate::graph::*;
 use std::collections::{HashMap, HashSet};
@@ -33,7 +35,7 @@ pub fn genre(data: &DataFrame) -> HashMap<String, Vec<ColumnVal>> {
                         for genre in genres.clone() {
                             genres_hash
                                 .entry(genre)
-                                .or_insert_with(HashSet::new)
+                                .or_default()
                                 .insert(actor_name.clone());
                         }
                     }
@@ -55,6 +57,7 @@ pub fn genre(data: &DataFrame) -> HashMap<String, Vec<ColumnVal>> {
 // Creates a bfs for actors in a specfic genre
 // Inputs: a dataframe, and a hashmap of actors and their connections
 // Outputs explained individually
+#[allow(clippy::type_complexity)]
 pub fn genres_bfs(
     data: DataFrame,
     hash: HashMap<ColumnVal, Vec<String>>,
@@ -67,7 +70,7 @@ pub fn genres_bfs(
         >, // That actor's friends
         Graph,                    // A graph for that genre
         Vec<(usize, usize, u32)>, // A vector containing tuples with the start node, end node, and distance b/w them
-        u32,                      // The average distance between actors in that genre
+        f64,                      // The average distance between actors in that genre
     ),
 > {
     // Create an empty hashmap
@@ -90,3 +93,282 @@ pub fn genres_bfs(
 
     genres_meta_hash
 }
+
+// Calculate the mean age of the actors in each genre
+// Inputs: a dataframe with ages (like combined.csv), and a dataframe with genres (like imdb_top_1000.csv)
+// Output: a hashmap containing genres and the mean age of their actors (genres with no known ages are left out)
+pub fn genre_ages(ages_data: DataFrame, genre_data: &DataFrame) -> HashMap<String, f64> {
+    // Look up each actor's age by name, skipping the actors age() couldn't calculate an age for
+    let ages: HashMap<String, i64> = age(ages_data)
+        .into_iter()
+        .filter_map(|(actor, maybe_age)| match maybe_age {
+            Some(ColumnVal::Two(val)) => Some((actor, val)),
+            _ => None,
+        })
+        .collect();
+
+    let mut genre_means = HashMap::new();
+    for (genre, actors) in genre(genre_data) {
+        let known: Vec<i64> = actors
+            .iter()
+            .filter_map(|actor| ages.get(&actor.to_string()).copied())
+            .collect();
+        if !known.is_empty() {
+            let mean = known.iter().sum::<i64>() as f64 / known.len() as f64;
+            genre_means.insert(genre, mean);
+        }
+    }
+
+    genre_means
+}
+
+// Find the pairs of collaborators whose edge only shows up in one genre's subgraph
+// Inputs: a dataframe, and a hashmap of actors and their connections
+// Output: a sorted vector of (actor, actor, genre) tuples, with the two actors in alphabetical order
+pub fn genre_unique_collaborations(
+    data: DataFrame,
+    hash: HashMap<ColumnVal, Vec<String>>,
+) -> Vec<(String, String, String)> {
+    // For every edge, store each genre whose subgraph it's in
+    let mut edge_genres: HashMap<(String, String), Vec<String>> = HashMap::new();
+    for (genre, (genre_hash, _, _, _)) in genres_bfs(data, hash) {
+        let mut edges = HashSet::new();
+        for (actor, friends) in &genre_hash {
+            let actor = actor.to_string();
+            for friend in friends {
+                // The edge is only in the subgraph if both actors are in the genre
+                if genre_hash.contains_key(&ColumnVal::One(friend.clone())) {
+                    let pair = if actor < *friend {
+                        (actor.clone(), friend.clone())
+                    } else {
+                        (friend.clone(), actor.clone())
+                    };
+                    edges.insert(pair);
+                }
+            }
+        }
+        for edge in edges {
+            edge_genres.entry(edge).or_default().push(genre.clone());
+        }
+    }
+
+    let mut unique: Vec<(String, String, String)> = edge_genres
+        .into_iter()
+        .filter(|(_, genres)| genres.len() == 1)
+        .map(|((a, b), mut genres)| (a, b, genres.remove(0)))
+        .collect();
+    unique.sort();
+    unique
+}
+
+// Calculate how far apart two genres' actors are on average
+// Builds the subgraph over the actors of both genres, and only averages the distances from an actor in the first genre
+// to a different actor in the second (unreachable pairs are left out)
+// Inputs: a dataframe, a hashmap of actors and their connections, and the two genres
+// Output: the average cross-genre distance, or None if there are no connected cross-genre pairs
+pub fn cross_genre_average(
+    data: &DataFrame,
+    hash: HashMap<ColumnVal, Vec<String>>,
+    genre_a: &str,
+    genre_b: &str,
+) -> Option<f64> {
+    let genres = genre(data);
+    let (graph, index_map) = crate::graph::hash_graph_with_index(hash);
+
+    // Turn each genre's actors into vertices of the global graph
+    let members = |g: &str| -> HashSet<Vertex> {
+        genres
+            .get(&g.to_lowercase())
+            .map(|actors| {
+                actors
+                    .iter()
+                    .filter_map(|actor| index_map.get(&actor.to_string()).copied())
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let in_a = members(genre_a);
+    let in_b = members(genre_b);
+
+    let mut vertices: Vec<Vertex> = in_a.union(&in_b).copied().collect();
+    vertices.sort();
+    let subgraph = graph.induced_subgraph(&vertices);
+
+    // Vertex i of the subgraph is vertices[i] of the global graph
+    let distances: Vec<u32> = subgraph
+        .bfs()
+        .0
+        .into_iter()
+        .filter(|&(u, v, _)| u != v && in_a.contains(&vertices[u]) && in_b.contains(&vertices[v]))
+        .map(|(_, _, d)| d)
+        .collect();
+
+    if distances.is_empty() {
+        None
+    } else {
+        Some(distances.iter().sum::<u32>() as f64 / distances.len() as f64)
+    }
+}
+
+// Count how many films each actor has in each genre
+// Input: a dataframe
+// Output: a hashmap containing genres, and a hashmap of each of their actors and the number of films in that genre
+pub fn genre_film_counts(data: &DataFrame) -> HashMap<String, HashMap<String, usize>> {
+    let genre_index = data
+        .labels
+        .iter()
+        .position(|label| label == "Genre")
+        .expect("Genre column not found");
+
+    let mut counts: HashMap<String, HashMap<String, usize>> = HashMap::new();
+    for row in &data.table {
+        // An actor listed twice in the same film still only counts once
+        let actors: HashSet<String> = data
+            .labels
+            .iter()
+            .enumerate()
+            .filter(|(_, label)| label.contains("Star"))
+            .filter_map(|(i, _)| match &row[i] {
+                ColumnVal::One(actor) if !actor.trim().is_empty() => Some(actor.clone()),
+                _ => None,
+            })
+            .collect();
+        for genre in row[genre_index].to_string().split(',') {
+            let genre_counts = counts.entry(genre.trim().to_lowercase()).or_default();
+            for actor in &actors {
+                *genre_counts.entry(actor.clone()).or_default() += 1;
+            }
+        }
+    }
+    counts
+}
+
+// The bfs average for each genre, both as is and with each actor's distances weighted by their film count in that genre
+// (So prolific actors, who anchor more of the genre, count for more)
+// Inputs: a dataframe, and a hashmap of actors and their connections
+// Output: a hashmap containing genres and their (unweighted, weighted) averages
+// (None if the genre's graph has fewer than two actors or no edges, like subgroup_average)
+pub fn genres_bfs_weighted(
+    data: DataFrame,
+    hash: HashMap<ColumnVal, Vec<String>>,
+) -> HashMap<String, Option<(f64, f64)>> {
+    let film_counts = genre_film_counts(&data);
+    let mut averages = HashMap::new();
+
+    // Reuse each genre's graph and bfs from genres_bfs, and weight the distances from each actor
+    for (genre, (_, genre_graph, distances, unweighted)) in genres_bfs(data, hash) {
+        if !genre_graph.has_subgroup_data() {
+            averages.insert(genre, None);
+            continue;
+        }
+
+        let weights: Vec<f64> = (0..genre_graph.n)
+            .map(|v| {
+                film_counts
+                    .get(&genre)
+                    .and_then(|counts| counts.get(&genre_graph.label(v)))
+                    .copied()
+                    .unwrap_or(0) as f64
+            })
+            .collect();
+        let weighted_total: f64 = distances
+            .iter()
+            .map(|&(start, _, d)| weights[start] * d as f64)
+            .sum();
+        let total_weight: f64 = distances.iter().map(|&(start, _, _)| weights[start]).sum();
+        let weighted = if total_weight > 0.0 {
+            weighted_total / total_weight
+        } else {
+            unweighted
+        };
+        averages.insert(genre, Some((unweighted, weighted)));
+    }
+
+    averages
+}
+
+// Store the genres each actor has been in (the reverse of genre)
+// Input: a dataframe
+// Output: a hashmap containing actors and the set of genres they've been in
+pub fn actor_genres(data: &DataFrame) -> HashMap<String, HashSet<String>> {
+    let mut actors_hash: HashMap<String, HashSet<String>> = HashMap::new();
+    for (genre, actors) in genre(data) {
+        for actor in actors {
+            actors_hash
+                .entry(actor.to_string())
+                .or_default()
+                .insert(genre.clone());
+        }
+    }
+    actors_hash
+}
+
+// Pivot the genres into a wide table: one row per actor, and a 0/1 column for each genre
+// Input: a dataframe
+// Output: a dataframe with an "actor" column, then one column per genre (in alphabetical order), rows sorted by actor
+pub fn pivot_actor_genre(data: &DataFrame) -> DataFrame {
+    let actors_hash = actor_genres(data);
+
+    let mut genres: Vec<String> = actors_hash.values().flatten().cloned().collect();
+    genres.sort();
+    genres.dedup();
+    let mut actors: Vec<&String> = actors_hash.keys().collect();
+    actors.sort();
+
+    let mut pivot = DataFrame::new();
+    pivot.labels = std::iter::once("actor".to_string())
+        .chain(genres.iter().cloned())
+        .collect();
+    pivot.types = vec![2; pivot.labels.len()];
+    pivot.types[0] = 1;
+    pivot.coercions = vec![0; pivot.labels.len()];
+    for actor in actors {
+        let mut row = vec![ColumnVal::One(actor.clone())];
+        for genre in &genres {
+            row.push(ColumnVal::Two(actors_hash[actor].contains(genre) as i64));
+        }
+        pivot.table.push(row);
+    }
+    pivot
+}
+
+// How far an actor is, on average, from the other actors who share at least one genre with them
+// Builds the subgraph over the actor and their genre peers, and averages the distances from the actor to the peers they can reach
+// Inputs: a dataframe, a hashmap of actors and their connections, and the actor's name
+// Output: the average distance, or None if the actor has no peers in the graph or can't reach any of them
+pub fn genre_peer_average(
+    data: &DataFrame,
+    hash: HashMap<ColumnVal, Vec<String>>,
+    actor: &str,
+) -> Option<f64> {
+    let actors_hash = actor_genres(data);
+    let own_genres = actors_hash.get(actor)?;
+    let (graph, index_map) = crate::graph::hash_graph_with_index(hash);
+    let center = *index_map.get(actor)?;
+
+    // The actor goes first, so they're vertex 0 of the subgraph
+    let mut vertices = vec![center];
+    for (other, genres) in &actors_hash {
+        if other != actor && !genres.is_disjoint(own_genres) {
+            if let Some(&v) = index_map.get(other) {
+                vertices.push(v);
+            }
+        }
+    }
+    if vertices.len() < 2 {
+        return None;
+    }
+
+    let subgraph = graph.induced_subgraph(&vertices);
+    let distances: Vec<u32> = subgraph
+        .bfs()
+        .0
+        .into_iter()
+        .filter(|&(u, v, _)| u == 0 && v != 0)
+        .map(|(_, _, d)| d)
+        .collect();
+    if distances.is_empty() {
+        return None;
+    }
+    Some(distances.iter().sum::<u32>() as f64 / distances.len() as f64)
+}
@@ -1,19 +1,38 @@
 // This mod allows me to perform graph operations as well as to process a dataframe into a graph, and a graph into a csv
+#![allow(dead_code)]
 
 pub type Vertex = usize;
 pub type ListOfEdges = Vec<(Vertex, Vertex)>;
 pub type AdjacencyLists = Vec<Vec<Vertex>>;
 use crate::csv::*;
-use std::collections::{HashMap, HashSet, VecDeque};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BinaryHeap, HashMap, HashSet, VecDeque};
 use std::error::Error;
 use std::fs::File;
 use std::io::Write;
 
+// The most neighbors of an actor that max_clique_containing will search through
+pub const MAX_CLIQUE_NEIGHBORHOOD: usize = 64;
+
 // Create a graph that contains vertex labels and a list of adjacent edges
 #[derive(Debug, Clone)]
 pub struct Graph {
     pub n: usize, // Vertex labels in {0,...,n-1}
     pub outedges: AdjacencyLists,
+    pub labels: Vec<String>, // The actor at each vertex (empty if the graph wasn't built from actors)
+}
+
+// A summary of the largest connected component
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentStats {
+    pub vertices: usize, // Number of actors in the component
+    pub edges: usize,    // Number of distinct collaborations between them
+    pub average: f64,    // Average distance over unordered pairs of different actors
+    pub diameter: u32,   // Longest shortest distance
+    pub coverage: f64,   // Fraction of the whole graph's vertices that are in the component
 }
 
 // Reverse direction of edges on a list
@@ -54,6 +73,7 @@ impl Graph {
         let mut g = Graph {
             n,
             outedges: vec![vec![]; n],
+            labels: vec![],
         };
         g.add_directed_edges(edges);
         g.sort_graph_lists();
@@ -72,55 +92,782 @@ impl Graph {
     }
 
     // Implement bfs
+    // The average is over ordered pairs of different, connected actors: both (a, b, d) and (b, a, d) are counted,
+    // but an actor's distance 0 to themself isn't (it would pull the average towards 0), and neither are unreachable pairs
+    // (see bfs_unordered for the conventional average over pairs a < b)
     // Input: self
-    // Outputs: a vector of tuples that contain start node, end node, and distance; average distance
-    pub fn bfs(&self) -> (Vec<(usize, usize, u32)>, u32) {
+    // Outputs: a vector of tuples that contain start node, end node, and distance; average distance (not rounded, 0.0 for an empty graph)
+    pub fn bfs(&self) -> (Vec<(usize, usize, u32)>, f64) {
         // Create an empty distances vector
         let mut distances = vec![];
 
         // For each actor (stored as a number), calculate the distance to that actor
         for actor in 0..self.n {
-            // Initially all distances are none (unvisited)
-            // Distance to self is 0
-            let mut distance: Vec<Option<u32>> = vec![None; self.n];
-            distance[actor] = Some(0);
+            let distance = self.bfs_from(actor);
 
-            // Create an empty queue, and start from the actor
-            let mut queue: VecDeque<Vertex> = VecDeque::new();
-            queue.push_back(actor);
+            // Iterate through all the distances, and if there's a distance to another actor v, add that distance to distances
+            for (v, d) in distance.iter().enumerate() {
+                if let Some(d) = d {
+                    if v != actor {
+                        distances.push((actor, v, *d));
+                    }
+                }
+            }
+        }
+
+        // Calculate the average distance by returning a sum of all of the distances divided by the length of distances
+        let total_distance: u32 = distances.iter().map(|&(_, _, d)| d).sum();
+        let average_distance = if !distances.is_empty() {
+            total_distance as f64 / distances.len() as f64
+        } else {
+            0.0
+        };
+
+        // Return a duple containing the distances vector and the average distance
+        (distances, average_distance)
+    }
 
-            // Pop the next vertex (v), and look at its neigbors (u)
-            // If u hasn't been visited, assign it's distance as one more than the distance to v, enqueue it
+    // Whether a subgroup (like an age bracket or genre) has enough data for its bfs average to mean anything
+    // (An average of 0 from a graph with no edges would look like a tightly connected group)
+    // Input: self
+    // Output: false if the graph has fewer than two vertices or no edges
+    pub fn has_subgroup_data(&self) -> bool {
+        self.n >= 2 && self.outedges.iter().any(|l| !l.is_empty())
+    }
+
+    // The bfs average for a subgroup, or None if there isn't enough data (see has_subgroup_data)
+    // Input: self
+    // Output: the average distance, or None if the graph has fewer than two vertices or no edges
+    pub fn subgroup_average(&self) -> Option<f64> {
+        if !self.has_subgroup_data() {
+            return None;
+        }
+        Some(self.bfs().1)
+    }
+
+    // Same as bfs, but each pair of different actors is only counted once (start node < end node)
+    // This is the conventional definition of the average shortest path length, so it matches hand calculations
+    // Input: self
+    // Outputs: a vector of tuples that contain start node, end node, and distance; average distance
+    pub fn bfs_unordered(&self) -> (Vec<(usize, usize, u32)>, f64) {
+        let distances: Vec<(usize, usize, u32)> = self
+            .bfs()
+            .0
+            .into_iter()
+            .filter(|&(u, v, _)| u < v)
+            .collect();
+
+        let total_distance: u32 = distances.iter().map(|&(_, _, d)| d).sum();
+        let average_distance = if !distances.is_empty() {
+            total_distance as f64 / distances.len() as f64
+        } else {
+            0.0
+        };
+
+        (distances, average_distance)
+    }
+
+    // Calculate the Wiener index: the sum of the shortest distances over all unordered pairs in the largest component
+    // (A single number to compare how compact different subgraphs are)
+    // Input: self, output: the Wiener index
+    pub fn wiener_index(&self) -> u64 {
+        let distances = self.bfs().0;
+
+        // Every vertex reaches exactly its own component, so the vertex that reaches the most vertices is in the largest one
+        let mut reach = vec![0; self.n];
+        for &(u, _, _) in &distances {
+            reach[u] += 1;
+        }
+        let root = match (0..self.n).max_by_key(|&v| reach[v]) {
+            Some(root) => root,
+            None => return 0,
+        };
+        let mut largest: HashSet<Vertex> = distances
+            .iter()
+            .filter(|&&(u, _, _)| u == root)
+            .map(|&(_, v, _)| v)
+            .collect();
+        largest.insert(root);
+
+        distances
+            .iter()
+            .filter(|&&(u, v, _)| u < v && largest.contains(&u))
+            .map(|&(_, _, d)| d as u64)
+            .sum()
+    }
+
+    // Bfs from a single vertex, for when the distances from one actor are enough
+    // Inputs: self, the source vertex
+    // Output: the distance from source to every vertex (None if unreachable, and all None if source is out of range)
+    pub fn bfs_from(&self, source: Vertex) -> Vec<Option<u32>> {
+        // Initially all distances are none (unvisited)
+        let mut distance: Vec<Option<u32>> = vec![None; self.n];
+        if source >= self.n {
+            return distance;
+        }
+
+        // Distance to self is 0, and start the queue from the source
+        distance[source] = Some(0);
+        let mut queue: VecDeque<Vertex> = VecDeque::new();
+        queue.push_back(source);
+
+        // Pop the next vertex (v), and look at its neigbors (u)
+        // If u hasn't been visited, assign it's distance as one more than the distance to v, enqueue it
+        while let Some(v) = queue.pop_front() {
+            for &u in &self.outedges[v] {
+                if distance[u].is_none() {
+                    distance[u] = Some(distance[v].unwrap() + 1);
+                    queue.push_back(u);
+                }
+            }
+        }
+        distance
+    }
+
+    // The eccentricity of a vertex: the furthest it is from anyone it can reach (unreachable vertices are ignored)
+    // Inputs: self, the vertex
+    // Output: the largest finite distance from v (None if v is out of range)
+    pub fn eccentricity(&self, v: Vertex) -> Option<u32> {
+        self.bfs_from(v).into_iter().flatten().max()
+    }
+
+    // The diameter: the largest eccentricity, so the longest shortest path between any two connected actors
+    // Input: self, output: the diameter (None if the graph is empty)
+    pub fn diameter(&self) -> Option<u32> {
+        (0..self.n).filter_map(|v| self.eccentricity(v)).max()
+    }
+
+    // Count how many other actors can be reached from source within n hops, using a bfs that stops at depth n
+    // Inputs: self, the source vertex, the number of hops
+    // Output: the number of vertices reached (not counting source, and 0 if source is out of range)
+    pub fn reach_within(&self, source: Vertex, n: u32) -> usize {
+        if source >= self.n {
+            return 0;
+        }
+        let mut distance: Vec<Option<u32>> = vec![None; self.n];
+        distance[source] = Some(0);
+        let mut queue: VecDeque<Vertex> = VecDeque::new();
+        queue.push_back(source);
+        let mut reached = 0;
+        while let Some(v) = queue.pop_front() {
+            let d = distance[v].unwrap();
+            if d == n {
+                continue;
+            }
+            for &u in &self.outedges[v] {
+                if distance[u].is_none() {
+                    distance[u] = Some(d + 1);
+                    reached += 1;
+                    queue.push_back(u);
+                }
+            }
+        }
+        reached
+    }
+
+    // reach_within for every vertex
+    // Inputs: self, the number of hops; output: a vector with each vertex's reach
+    pub fn all_reach_within(&self, n: u32) -> Vec<usize> {
+        (0..self.n).map(|v| self.reach_within(v, n)).collect()
+    }
+
+    // Starting from the most-connected actor (the max-degree vertex), the fraction of the other actors within k hops
+    // Input: self, the largest k
+    // Output: the cumulative fraction for k = 1, ..., max_k (empty if the graph has fewer than two vertices)
+    pub fn hub_reach_fractions(&self, max_k: u32) -> Vec<f64> {
+        if self.n < 2 {
+            return vec![];
+        }
+        let hub = (0..self.n).max_by_key(|&v| self.outedges[v].len()).unwrap();
+        (1..=max_k)
+            .map(|k| self.reach_within(hub, k) as f64 / (self.n - 1) as f64)
+            .collect()
+    }
+
+    // Betweenness centrality of every vertex, using Brandes' algorithm
+    // Raw scores count each unordered pair of other vertices once, weighted by the share of their shortest paths through the vertex
+    // Normalized scores are divided by the number of such pairs, (n-1)(n-2)/2, so they're in [0, 1]
+    // Inputs: self, whether to normalize; output: the score of each vertex
+    pub fn betweenness(&self, normalized: bool) -> Vec<f64> {
+        let mut centrality = vec![0.0; self.n];
+
+        for source in 0..self.n {
+            // Bfs from source, counting shortest paths (sigma) and recording the order vertices were reached in
+            let mut distance: Vec<Option<u32>> = vec![None; self.n];
+            let mut sigma = vec![0.0; self.n];
+            let mut order = vec![];
+            distance[source] = Some(0);
+            sigma[source] = 1.0;
+            let mut queue: VecDeque<Vertex> = VecDeque::new();
+            queue.push_back(source);
             while let Some(v) = queue.pop_front() {
+                order.push(v);
+                let next = distance[v].unwrap() + 1;
                 for &u in &self.outedges[v] {
                     if distance[u].is_none() {
-                        distance[u] = Some(distance[v].unwrap() + 1);
+                        distance[u] = Some(next);
                         queue.push_back(u);
                     }
+                    if distance[u] == Some(next) {
+                        sigma[u] += sigma[v];
+                    }
                 }
             }
 
-            // Iterate through all the distances, and if there's a distance to v, add that distance to distances
-            for v in 0..self.n {
-                if let Some(d) = distance[v] {
-                    distances.push((actor, v, d));
+            // Walk back from the furthest vertices, accumulating each vertex's dependency
+            let mut delta = vec![0.0; self.n];
+            for &w in order.iter().rev() {
+                for &v in &self.outedges[w] {
+                    if distance[v].is_some() && distance[v].unwrap() + 1 == distance[w].unwrap() {
+                        delta[v] += sigma[v] / sigma[w] * (1.0 + delta[w]);
+                    }
+                }
+                if w != source {
+                    centrality[w] += delta[w];
                 }
             }
         }
 
-        // Calculate the average distance by returning a sum of all of the distances divided by the length of distances
-        let total_distance: u32 = distances.iter().map(|&(_, _, d)| d).sum();
-        let average_distance = if !distances.is_empty() {
-            total_distance / distances.len() as u32
+        // Every pair was counted from both ends
+        for c in centrality.iter_mut() {
+            *c /= 2.0;
+        }
+        if normalized && self.n > 2 {
+            let pairs = ((self.n - 1) * (self.n - 2)) as f64 / 2.0;
+            for c in centrality.iter_mut() {
+                *c /= pairs;
+            }
+        }
+        centrality
+    }
+
+    // Closeness centrality of every vertex, counting only the vertices it can reach
+    // Raw scores are 1 / (sum of distances), normalized scores are (vertices reached) / (sum of distances), in [0, 1]
+    // Inputs: self, whether to normalize; output: the score of each vertex (0.0 if it reaches nobody)
+    pub fn closeness(&self, normalized: bool) -> Vec<f64> {
+        (0..self.n)
+            .map(|v| {
+                let reached: Vec<u32> = self
+                    .bfs_from(v)
+                    .into_iter()
+                    .flatten()
+                    .filter(|&d| d > 0)
+                    .collect();
+                let total: u32 = reached.iter().sum();
+                if total == 0 {
+                    0.0
+                } else if normalized {
+                    reached.len() as f64 / total as f64
+                } else {
+                    1.0 / total as f64
+                }
+            })
+            .collect()
+    }
+
+    // Harmonic centrality of every vertex: the sum of 1 / distance to every other vertex (unreachable ones add 0)
+    // Normalized scores are divided by n - 1, so they're in [0, 1]
+    // Inputs: self, whether to normalize; output: the score of each vertex
+    pub fn harmonic(&self, normalized: bool) -> Vec<f64> {
+        (0..self.n)
+            .map(|v| {
+                let total: f64 = self
+                    .bfs_from(v)
+                    .into_iter()
+                    .flatten()
+                    .filter(|&d| d > 0)
+                    .map(|d| 1.0 / d as f64)
+                    .sum();
+                if normalized && self.n > 1 {
+                    total / (self.n - 1) as f64
+                } else {
+                    total
+                }
+            })
+            .collect()
+    }
+
+    // Calculate the friendship-paradox ratio: are an actor's collaborators better connected than the actor?
+    // The mean over vertices of (average degree of its neighbors) / (its own degree), skipping isolated vertices
+    // Input: self, output: the ratio (above 1 means collaborators tend to be better connected, 0.0 if there are no edges)
+    pub fn friendship_paradox_ratio(&self) -> f64 {
+        let ratios: Vec<f64> = self
+            .outedges
+            .iter()
+            .filter(|l| !l.is_empty())
+            .map(|l| {
+                let neighbor_degrees: usize = l.iter().map(|&u| self.outedges[u].len()).sum();
+                let average_neighbor_degree = neighbor_degrees as f64 / l.len() as f64;
+                average_neighbor_degree / l.len() as f64
+            })
+            .collect();
+
+        if ratios.is_empty() {
+            0.0
+        } else {
+            ratios.iter().sum::<f64>() / ratios.len() as f64
+        }
+    }
+
+    // Count the triangles (three actors who all worked with each other) in the graph
+    // For each edge v < u, walk through both sorted neighbor lists at once to find their common neighbors w > u
+    // (So each triangle v < u < w is counted once)
+    // Input: self, output: the number of triangles
+    pub fn count_triangles(&self) -> usize {
+        let mut triangles = 0;
+        for v in 0..self.n {
+            for &u in self.outedges[v].iter().filter(|&&u| u > v) {
+                let (a, b) = (&self.outedges[v], &self.outedges[u]);
+                let (mut i, mut j) = (0, 0);
+                while i < a.len() && j < b.len() {
+                    match a[i].cmp(&b[j]) {
+                        Ordering::Less => i += 1,
+                        Ordering::Greater => j += 1,
+                        Ordering::Equal => {
+                            if a[i] > u {
+                                triangles += 1;
+                            }
+                            i += 1;
+                            j += 1;
+                        }
+                    }
+                }
+            }
+        }
+        triangles
+    }
+
+    // Calculate the transitivity (global clustering coefficient): 3 * triangles / connected triples
+    // A connected triple is a path of length 2, and each vertex is the middle of C(degree, 2) of them
+    // Input: self, output: the transitivity (0.0 if there are no connected triples)
+    pub fn transitivity(&self) -> f64 {
+        let triples: usize = self
+            .outedges
+            .iter()
+            .map(|l| l.len() * l.len().saturating_sub(1) / 2)
+            .sum();
+        if triples == 0 {
+            return 0.0;
+        }
+        3.0 * self.count_triangles() as f64 / triples as f64
+    }
+
+    // The local clustering coefficient: the fraction of pairs of v's collaborators who also worked with each other
+    // Each pair of neighbors is only checked once, and there are k(k-1)/2 of them for k neighbors
+    // Inputs: self, the vertex
+    // Output: the coefficient (0.0 if v has fewer than two neighbors, or is out of range)
+    pub fn clustering_coefficient(&self, v: Vertex) -> f64 {
+        if v >= self.n {
+            return 0.0;
+        }
+        let neighbors: Vec<Vertex> = self.outedges[v]
+            .iter()
+            .copied()
+            .filter(|&u| u != v)
+            .collect();
+        let k = neighbors.len();
+        if k < 2 {
+            return 0.0;
+        }
+
+        let mut linked = 0;
+        for (i, &u) in neighbors.iter().enumerate() {
+            for &w in &neighbors[i + 1..] {
+                if self.outedges[u].binary_search(&w).is_ok() {
+                    linked += 1;
+                }
+            }
+        }
+        linked as f64 / (k * (k - 1) / 2) as f64
+    }
+
+    // The average of every vertex's clustering coefficient (vertices with fewer than two neighbors count as 0.0)
+    // Input: self, output: the average (0.0 for an empty graph)
+    pub fn average_clustering(&self) -> f64 {
+        if self.n == 0 {
+            return 0.0;
+        }
+        let total: f64 = (0..self.n).map(|v| self.clustering_coefficient(v)).sum();
+        total / self.n as f64
+    }
+
+    // Find a shortest path from src to dst, using a bfs that remembers which vertex each vertex was reached from
+    // Inputs: self, source and destination vertices
+    // Output: the vertices on the path, from src to dst (None if dst is unreachable or either vertex is out of range)
+    pub fn shortest_path(&self, src: Vertex, dst: Vertex) -> Option<Vec<Vertex>> {
+        if src >= self.n || dst >= self.n {
+            return None;
+        }
+
+        let mut parent: Vec<Option<Vertex>> = vec![None; self.n];
+        let mut seen = vec![false; self.n];
+        seen[src] = true;
+        let mut queue: VecDeque<Vertex> = VecDeque::new();
+        queue.push_back(src);
+        while let Some(v) = queue.pop_front() {
+            if v == dst {
+                break;
+            }
+            for &u in &self.outedges[v] {
+                if !seen[u] {
+                    seen[u] = true;
+                    parent[u] = Some(v);
+                    queue.push_back(u);
+                }
+            }
+        }
+        if !seen[dst] {
+            return None;
+        }
+
+        // Walk back from dst to src
+        let mut path = vec![dst];
+        let mut current = dst;
+        while let Some(previous) = parent[current] {
+            path.push(previous);
+            current = previous;
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    // Count the shortest paths from source to target (the sigma array from Brandes' algorithm)
+    // Inputs: self, source and target vertices
+    // Output: the number of shortest paths (0 if target is unreachable, or either vertex is out of range)
+    pub fn num_shortest_paths(&self, source: Vertex, target: Vertex) -> u64 {
+        if source >= self.n || target >= self.n {
+            return 0;
+        }
+        let mut distance: Vec<Option<u32>> = vec![None; self.n];
+        let mut sigma: Vec<u64> = vec![0; self.n];
+        distance[source] = Some(0);
+        sigma[source] = 1;
+
+        let mut queue: VecDeque<Vertex> = VecDeque::new();
+        queue.push_back(source);
+
+        // Same as bfs, but every time u is reached from v along a shortest path, add v's path count to u's
+        while let Some(v) = queue.pop_front() {
+            let next = distance[v].unwrap() + 1;
+            for &u in &self.outedges[v] {
+                if distance[u].is_none() {
+                    distance[u] = Some(next);
+                    queue.push_back(u);
+                }
+                if distance[u] == Some(next) {
+                    sigma[u] += sigma[v];
+                }
+            }
+        }
+
+        sigma[target]
+    }
+
+    // Find the largest clique (group where everyone is connected to everyone) that contains v
+    // Uses a Bron-Kerbosch search restricted to v's neighbors, which is exponential in the worst case
+    // To keep that bounded, only the first MAX_CLIQUE_NEIGHBORHOOD neighbors of v are searched
+    // Inputs: self, a vertex; output: the vertices of the clique, sorted (empty if v is out of range)
+    pub fn max_clique_containing(&self, v: Vertex) -> Vec<Vertex> {
+        if v >= self.n {
+            return vec![];
+        }
+        let candidates: HashSet<Vertex> = self.outedges[v]
+            .iter()
+            .copied()
+            .filter(|&u| u != v)
+            .take(MAX_CLIQUE_NEIGHBORHOOD)
+            .collect();
+
+        let mut best = vec![v];
+        self.bron_kerbosch(&mut vec![v], candidates, HashSet::new(), &mut best);
+        best.sort();
+        best
+    }
+
+    // Bron-Kerbosch with pivoting, keeping the largest clique found in best
+    // Inputs: self, the current clique, vertices that could extend it, vertices already tried, the best clique so far
+    fn bron_kerbosch(
+        &self,
+        clique: &mut Vec<Vertex>,
+        mut candidates: HashSet<Vertex>,
+        mut excluded: HashSet<Vertex>,
+        best: &mut Vec<Vertex>,
+    ) {
+        if candidates.is_empty() {
+            if excluded.is_empty() && clique.len() > best.len() {
+                *best = clique.clone();
+            }
+            return;
+        }
+        // Nothing left can beat the best clique
+        if clique.len() + candidates.len() <= best.len() {
+            return;
+        }
+
+        // Only branch on candidates that aren't neighbors of the pivot
+        let pivot = *candidates.union(&excluded).next().unwrap();
+        let branches: Vec<Vertex> = candidates
+            .iter()
+            .copied()
+            .filter(|u| self.outedges[pivot].binary_search(u).is_err())
+            .collect();
+
+        for u in branches {
+            let neighbors: HashSet<Vertex> = self.outedges[u].iter().copied().collect();
+            clique.push(u);
+            self.bron_kerbosch(
+                clique,
+                candidates.intersection(&neighbors).copied().collect(),
+                excluded.intersection(&neighbors).copied().collect(),
+                best,
+            );
+            clique.pop();
+            candidates.remove(&u);
+            excluded.insert(u);
+        }
+    }
+
+    // Split the graph into its connected components, using a bfs from every vertex that hasn't been reached yet
+    // Input: self, output: each component's vertices (sorted), largest component first
+    pub fn connected_components(&self) -> Vec<Vec<Vertex>> {
+        let mut seen = vec![false; self.n];
+        let mut components = vec![];
+        for start in 0..self.n {
+            if seen[start] {
+                continue;
+            }
+            seen[start] = true;
+            let mut component = vec![];
+            let mut queue: VecDeque<Vertex> = VecDeque::new();
+            queue.push_back(start);
+            while let Some(v) = queue.pop_front() {
+                component.push(v);
+                for &u in &self.outedges[v] {
+                    if !seen[u] {
+                        seen[u] = true;
+                        queue.push_back(u);
+                    }
+                }
+            }
+            component.sort();
+            components.push(component);
+        }
+        components.sort_by_key(|c| std::cmp::Reverse(c.len()));
+        components
+    }
+
+    // The subgraph of the largest connected component, renumbered 0..k in the same order as the original vertices
+    // (So bfs on it isn't mixed up with the small clusters)
+    // Input: self, output: the component as a graph (with no vertices if self has none)
+    pub fn largest_component(&self) -> Graph {
+        let largest = self
+            .connected_components()
+            .into_iter()
+            .next()
+            .unwrap_or_default();
+        self.induced_subgraph(&largest)
+    }
+
+    // Vertex and edge counts, f64 average distance, diameter and coverage of the largest component
+    // (The average is over the component only, so the vertex and edge counts describe the same actors it covers)
+    // Input: self
+    // Output: a ComponentStats (all zeros for an empty graph)
+    pub fn largest_component_stats(&self) -> ComponentStats {
+        let component = self.largest_component();
+        let (distances, average) = component.bfs_unordered();
+        let diameter = distances.iter().map(|&(_, _, d)| d).max().unwrap_or(0);
+        let coverage = if self.n > 0 {
+            component.n as f64 / self.n as f64
         } else {
-            0
+            0.0
         };
+        ComponentStats {
+            vertices: component.n,
+            edges: component.edge_count(),
+            average,
+            diameter,
+            coverage,
+        }
+    }
 
-        // Return a duple containing the distances vector and the average distance
-        (distances, average_distance)
+    // The bfs average of each connected component on its own, so one giant component doesn't hide the small ones
+    // Input: self
+    // Output: a (component size, average distance) tuple for each component, largest component first
+    pub fn per_component_averages(&self) -> Vec<(usize, f64)> {
+        self.connected_components()
+            .iter()
+            .map(|component| (component.len(), self.induced_subgraph(component).bfs().1))
+            .collect()
+    }
+
+    // The subgraph of everyone within radius hops of an actor (their "ego network"), for focused plots
+    // Inputs: self, the center vertex, the radius
+    // Outputs: the induced subgraph, and a hashmap from each kept vertex to its index in the subgraph
+    // (an empty graph and map if center is out of range)
+    pub fn ego_subgraph(&self, center: Vertex, radius: u32) -> (Graph, HashMap<Vertex, Vertex>) {
+        if center >= self.n {
+            return (Graph::create_undirected(0, &vec![]), HashMap::new());
+        }
+        // Bfs from the center that stops at depth radius
+        let mut distance: Vec<Option<u32>> = vec![None; self.n];
+        distance[center] = Some(0);
+        let mut queue: VecDeque<Vertex> = VecDeque::new();
+        queue.push_back(center);
+        while let Some(v) = queue.pop_front() {
+            let d = distance[v].unwrap();
+            if d == radius {
+                continue;
+            }
+            for &u in &self.outedges[v] {
+                if distance[u].is_none() {
+                    distance[u] = Some(d + 1);
+                    queue.push_back(u);
+                }
+            }
+        }
+
+        let vertices: Vec<Vertex> = (0..self.n).filter(|&v| distance[v].is_some()).collect();
+        let new_index = vertices.iter().enumerate().map(|(i, &v)| (v, i)).collect();
+        (self.induced_subgraph(&vertices), new_index)
+    }
+
+    // The bfs average of the largest component that's left after removing the k highest-degree vertices
+    // (To check whether a few hub actors are what makes the network a small world)
+    // Input: self, k
+    // Output: the average distance (0.0 if no vertices are left)
+    pub fn average_without_hubs(&self, k: usize) -> f64 {
+        let mut by_degree: Vec<Vertex> = (0..self.n).collect();
+        by_degree.sort_by_key(|&v| std::cmp::Reverse(self.outedges[v].len()));
+        let mut rest: Vec<Vertex> = by_degree.into_iter().skip(k).collect();
+        rest.sort();
+
+        self.induced_subgraph(&rest).largest_component().bfs().1
+    }
+
+    // The number of neighbors of each vertex
+    // (This is the out-degree, which for the undirected graphs built here is the same as the total degree)
+    // Input: self, output: a vector with each vertex's degree
+    pub fn degrees(&self) -> Vec<usize> {
+        self.outedges
+            .iter()
+            .map(|neighbors| neighbors.len())
+            .collect()
+    }
+
+    // How many vertices have each degree
+    // Input: self, output: a map from degree to the number of vertices with that degree, in increasing order of degree
+    pub fn degree_distribution(&self) -> BTreeMap<usize, usize> {
+        let mut distribution = BTreeMap::new();
+        for degree in self.degrees() {
+            *distribution.entry(degree).or_default() += 1;
+        }
+        distribution
+    }
+
+    // The fraction of all possible edges that are actually in the graph
+    // Input: self
+    // Output: the number of distinct edges divided by n(n-1)/2 (0.0 if there are fewer than two vertices)
+    pub fn density(&self) -> f64 {
+        if self.n < 2 {
+            return 0.0;
+        }
+        let possible = self.n * (self.n - 1) / 2;
+        self.edge_count() as f64 / possible as f64
+    }
+
+    // Count the distinct undirected edges, ignoring self-loops
+    // (The adjacency lists can hold the same neighbor twice, so each edge is only counted once)
+    fn edge_count(&self) -> usize {
+        let mut edges: HashSet<(Vertex, Vertex)> = HashSet::new();
+        for (v, neighbors) in self.outedges.iter().enumerate() {
+            for &u in neighbors {
+                if v != u {
+                    edges.insert((v.min(u), v.max(u)));
+                }
+            }
+        }
+        edges.len()
+    }
+
+    // Add the edges one at a time in a random order, and find when a giant component (more than half the vertices) first appears
+    // Inputs: self, the seed for shuffling the edges
+    // Output: the fraction of edges added at that point (1.0 if no component ever gets that big, including in an empty graph,
+    // and 0.0 for a single vertex, which is already more than half before any edges are added)
+    pub fn giant_component_threshold(&self, seed: u64) -> f64 {
+        match self.n {
+            0 => return 1.0,
+            1 => return 0.0,
+            _ => {}
+        }
+
+        let mut edges: ListOfEdges = vec![];
+        for (v, neighbors) in self.outedges.iter().enumerate() {
+            for &u in neighbors {
+                if v < u {
+                    edges.push((v, u));
+                }
+            }
+        }
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        edges.shuffle(&mut rng);
+
+        // Union-find, with each root storing the size of its component
+        let mut parent: Vec<Vertex> = (0..self.n).collect();
+        let mut size = vec![1; self.n];
+        for (added, &(v, u)) in edges.iter().enumerate() {
+            let (root_v, root_u) = (find(&mut parent, v), find(&mut parent, u));
+            if root_v == root_u {
+                continue;
+            }
+            let (big, small) = if size[root_v] >= size[root_u] {
+                (root_v, root_u)
+            } else {
+                (root_u, root_v)
+            };
+            parent[small] = big;
+            size[big] += size[small];
+            if 2 * size[big] > self.n {
+                return (added + 1) as f64 / edges.len() as f64;
+            }
+        }
+        1.0
+    }
+
+    // Keep only the given vertices and the edges between them
+    // Vertex vertices[i] becomes vertex i in the new graph
+    // Inputs: self, the vertices to keep; output: the induced subgraph
+    pub fn induced_subgraph(&self, vertices: &[Vertex]) -> Graph {
+        let new_index: HashMap<Vertex, Vertex> =
+            vertices.iter().enumerate().map(|(i, &v)| (v, i)).collect();
+
+        let mut edges: ListOfEdges = vec![];
+        for (i, &v) in vertices.iter().enumerate() {
+            for u in &self.outedges[v] {
+                if let Some(&j) = new_index.get(u) {
+                    edges.push((i, j));
+                }
+            }
+        }
+
+        let mut subgraph = Graph::create_directed(vertices.len(), &edges);
+        if !self.labels.is_empty() {
+            subgraph.labels = vertices.iter().map(|&v| self.labels[v].clone()).collect();
+        }
+        subgraph
+    }
+
+    // The name of a vertex: its actor if the graph has labels, otherwise its number
+    // Inputs: self, the vertex; output: the name
+    pub fn label(&self, v: Vertex) -> String {
+        match self.labels.get(v) {
+            Some(label) => label.clone(),
+            None => v.to_string(),
+        }
     }
 
     // Export my graph to a csv so it can be plotted
+    // Vertices are written as actor names if the graph has labels
     // Inputs: self, path
     // Outputs: A result containing a boxed dynamic error
     pub fn export_to_csv(&self, path: &str) -> Result<(), Box<dyn Error>> {
@@ -128,65 +875,664 @@ impl Graph {
         writeln!(file, "source,target")?;
         for (src, targets) in self.outedges.iter().enumerate() {
             for &dst in targets {
-                writeln!(file, "{},{}", src, dst)?;
+                writeln!(
+                    file,
+                    "{},{}",
+                    csv_field(&self.label(src)),
+                    csv_field(&self.label(dst))
+                )?;
             }
         }
         Ok(())
     }
+
+    // Read a graph back in from a source,target csv of vertex numbers (like export_to_csv writes for a graph without labels)
+    // The graph is undirected, with max id + 1 vertices
+    // Lines without two vertex numbers are skipped
+    // Input: path
+    // Output: a result containing the graph and a boxed dynamic error (if the file can't be read or the header is missing)
+    pub fn import_from_csv(path: &str) -> Result<Graph, Box<dyn Error>> {
+        let edges: ListOfEdges = read_edge_rows(path)?
+            .into_iter()
+            .filter_map(|(src, dst)| Some((src.parse().ok()?, dst.parse().ok()?)))
+            .collect();
+        let n = edges.iter().map(|&(u, v)| u.max(v) + 1).max().unwrap_or(0);
+        Ok(Graph::create_undirected(n, &edges))
+    }
+
+    // Same as import_from_csv, but for a csv of actor names (like export_to_csv writes for a graph with labels)
+    // The actors are numbered in the order they first appear, and kept as labels
+    // Lines without two non-empty names are skipped
+    // Input: path
+    // Output: a result containing the graph and a boxed dynamic error (if the file can't be read or the header is missing)
+    pub fn import_labeled_from_csv(path: &str) -> Result<Graph, Box<dyn Error>> {
+        let mut labels: Vec<String> = vec![];
+        let mut index: HashMap<String, Vertex> = HashMap::new();
+        let mut edges: ListOfEdges = vec![];
+        for (src, dst) in read_edge_rows(path)? {
+            let mut vertex = |name: String| {
+                *index.entry(name.clone()).or_insert_with(|| {
+                    labels.push(name);
+                    labels.len() - 1
+                })
+            };
+            let (u, v) = (vertex(src), vertex(dst));
+            edges.push((u, v));
+        }
+        let mut graph = Graph::create_undirected(labels.len(), &edges);
+        graph.labels = labels;
+        Ok(graph)
+    }
+
+    // Run bfs and export the distance between every pair of different reachable vertices to a csv
+    // Inputs: self, path
+    // Outputs: A result containing a boxed dynamic error
+    pub fn export_bfs_to_csv(&self, path: &str) -> Result<(), Box<dyn Error>> {
+        let mut file = File::create(path)?;
+        writeln!(file, "source,target,distance")?;
+        for (src, dst, distance) in self.bfs().0 {
+            writeln!(file, "{},{},{}", src, dst, distance)?;
+        }
+        Ok(())
+    }
+
+    // Export my graph to a csv with actor names and the number of films each pair shared, so edges can be sized by weight
+    // Inputs: self, path, and the weighted connections (from weighted_connections)
+    // Outputs: A result containing a boxed dynamic error
+    pub fn export_weighted_csv(
+        &self,
+        path: &str,
+        weights: &HashMap<ColumnVal, HashMap<String, u32>>,
+    ) -> Result<(), Box<dyn Error>> {
+        let mut file = File::create(path)?;
+        writeln!(file, "source,target,weight")?;
+        for (src, targets) in self.outedges.iter().enumerate() {
+            for &dst in targets {
+                let (src_label, dst_label) = (self.label(src), self.label(dst));
+                let weight = weights
+                    .get(&ColumnVal::One(src_label.clone()))
+                    .and_then(|collaborators| collaborators.get(&dst_label))
+                    .copied()
+                    .unwrap_or(1);
+                writeln!(
+                    file,
+                    "{},{},{}",
+                    csv_field(&src_label),
+                    csv_field(&dst_label),
+                    weight
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    // Export my graph as a GEXF file so it can be opened in Gephi with the actors' names
+    // Each undirected edge is written once
+    // Inputs: self, path
+    // Outputs: A result containing a boxed dynamic error
+    pub fn export_to_gexf(&self, path: &str) -> Result<(), Box<dyn Error>> {
+        let mut file = File::create(path)?;
+        writeln!(file, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(file, r#"<gexf xmlns="http://gexf.net/1.3" version="1.3">"#)?;
+        writeln!(file, r#"  <graph defaultedgetype="undirected">"#)?;
+
+        writeln!(file, "    <nodes>")?;
+        for id in 0..self.n {
+            writeln!(
+                file,
+                r#"      <node id="{}" label="{}"/>"#,
+                id,
+                escape_xml(&self.label(id))
+            )?;
+        }
+        writeln!(file, "    </nodes>")?;
+
+        writeln!(file, "    <edges>")?;
+        let mut edge_id = 0;
+        for (src, targets) in self.outedges.iter().enumerate() {
+            for &dst in targets.iter().filter(|&&dst| src < dst) {
+                writeln!(
+                    file,
+                    r#"      <edge id="{}" source="{}" target="{}"/>"#,
+                    edge_id, src, dst
+                )?;
+                edge_id += 1;
+            }
+        }
+        writeln!(file, "    </edges>")?;
+
+        writeln!(file, "  </graph>")?;
+        writeln!(file, "</gexf>")?;
+        Ok(())
+    }
+
+    // Check whether every edge also goes the other way (true for graphs made with create_undirected)
+    // Input: self, output: true if the graph is undirected
+    pub fn is_undirected(&self) -> bool {
+        self.outedges.iter().enumerate().all(|(v, neighbors)| {
+            neighbors
+                .iter()
+                .all(|&u| self.outedges[u].binary_search(&v).is_ok())
+        })
+    }
+
+    // Export my graph as GraphML, so it can be opened in Gephi
+    // An undirected graph writes each edge once, and actor names are included as a "label" attribute if the graph has labels
+    // Inputs: self, path
+    // Outputs: A result containing a boxed dynamic error
+    pub fn export_to_graphml(&self, path: &str) -> Result<(), Box<dyn Error>> {
+        let undirected = self.is_undirected();
+        let mut file = File::create(path)?;
+        writeln!(file, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(
+            file,
+            r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#
+        )?;
+        if !self.labels.is_empty() {
+            writeln!(
+                file,
+                r#"  <key id="label" for="node" attr.name="label" attr.type="string"/>"#
+            )?;
+        }
+        writeln!(
+            file,
+            r#"  <graph id="G" edgedefault="{}">"#,
+            if undirected { "undirected" } else { "directed" }
+        )?;
+
+        for v in 0..self.n {
+            match self.labels.get(v) {
+                Some(label) => writeln!(
+                    file,
+                    r#"    <node id="n{}"><data key="label">{}</data></node>"#,
+                    v,
+                    escape_xml(label)
+                )?,
+                None => writeln!(file, r#"    <node id="n{}"/>"#, v)?,
+            }
+        }
+
+        for (src, targets) in self.outedges.iter().enumerate() {
+            for &dst in targets.iter().filter(|&&dst| !undirected || src <= dst) {
+                writeln!(file, r#"    <edge source="n{}" target="n{}"/>"#, src, dst)?;
+            }
+        }
+
+        writeln!(file, "  </graph>")?;
+        writeln!(file, "</graphml>")?;
+        Ok(())
+    }
+
+    // Export my graph as a Graphviz DOT file, for drawing small subgraphs
+    // An undirected graph is written as a "graph" with each edge once, otherwise as a "digraph"
+    // Vertices are written as actor names if the graph has labels
+    // Inputs: self, path
+    // Outputs: A result containing a boxed dynamic error
+    pub fn export_to_dot(&self, path: &str) -> Result<(), Box<dyn Error>> {
+        let undirected = self.is_undirected();
+        let (kind, arrow) = if undirected {
+            ("graph", "--")
+        } else {
+            ("digraph", "->")
+        };
+
+        let mut file = File::create(path)?;
+        writeln!(file, "{} {{", kind)?;
+        // List every vertex, so ones without edges are still drawn
+        for v in 0..self.n {
+            writeln!(file, "    {};", dot_id(&self.label(v)))?;
+        }
+        for (src, targets) in self.outedges.iter().enumerate() {
+            for &dst in targets.iter().filter(|&&dst| !undirected || src <= dst) {
+                writeln!(
+                    file,
+                    "    {} {} {};",
+                    dot_id(&self.label(src)),
+                    arrow,
+                    dot_id(&self.label(dst))
+                )?;
+            }
+        }
+        writeln!(file, "}}")?;
+        Ok(())
+    }
+}
+
+// Find the root of a vertex in a union-find forest, shortening the path on the way
+// Inputs: the parent of each vertex, the vertex; output: the root
+fn find(parent: &mut [Vertex], v: Vertex) -> Vertex {
+    let mut root = v;
+    while parent[root] != root {
+        root = parent[root];
+    }
+    let mut v = v;
+    while parent[v] != root {
+        let next = parent[v];
+        parent[v] = root;
+        v = next;
+    }
+    root
+}
+
+// Quote a csv field if it contains a comma, quote or newline
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+// Quote a DOT id unless it's a plain number or a name made of letters, digits and underscores
+fn dot_id(text: &str) -> String {
+    let plain_name = text
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && text.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    let number = !text.is_empty() && text.chars().all(|c| c.is_ascii_digit());
+    if plain_name || number {
+        text.to_string()
+    } else {
+        format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+// Read the rows of a source,target csv, leaving out the ones where either field is empty
+// Input: path
+// Output: a result containing the (source, target) pairs and a boxed dynamic error (if the file can't be read or the header is missing)
+fn read_edge_rows(path: &str) -> Result<Vec<(String, String)>, Box<dyn Error>> {
+    let mut data = DataFrame::new();
+    data.read_csv(path, &[1, 1])?;
+    if data.labels != ["source", "target"] {
+        return Err(format!("{} is missing the source,target header", path).into());
+    }
+    Ok(data
+        .table
+        .iter()
+        .map(|row| (row[0].to_string(), row[1].to_string()))
+        .filter(|(src, dst)| !src.is_empty() && !dst.is_empty())
+        .collect())
+}
+
+// Escape the characters that aren't allowed inside an XML attribute
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+// A graph whose edges carry a traversal cost, used where frequent collaborators should count as "closer"
+// Each entry in outedges is a neighbor and the cost of the edge to it
+#[derive(Debug, Clone)]
+pub struct WeightedGraph {
+    pub n: usize,
+    pub outedges: Vec<Vec<(Vertex, f64)>>,
+}
+
+// Entry in dijkstra's priority queue, ordered so that the BinaryHeap pops the smallest cost first
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct State {
+    cost: f64,
+    vertex: Vertex,
+}
+
+impl Eq for State {}
+
+impl Ord for State {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.total_cmp(&self.cost)
+    }
+}
+
+impl PartialOrd for State {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl WeightedGraph {
+    // Create an undirected weighted graph (every edge is added in both directions)
+    // Inputs: n (the number of vertices), a list of edges as (u, v, cost)
+    // Outputs: a weighted graph
+    pub fn create_undirected(n: usize, edges: &[(Vertex, Vertex, f64)]) -> WeightedGraph {
+        let mut g = WeightedGraph {
+            n,
+            outedges: vec![vec![]; n],
+        };
+        for &(u, v, w) in edges {
+            g.outedges[u].push((v, w));
+            g.outedges[v].push((u, w));
+        }
+        g
+    }
+
+    // Create a weighted graph from the number of films each pair of actors shared
+    // The cost of an edge is 1/shared_films, so actors who worked together more often are closer
+    // Inputs: n (the number of vertices), a list of (u, v, shared films)
+    // Outputs: a weighted graph
+    pub fn from_shared_films(n: usize, counts: &[(Vertex, Vertex, u32)]) -> WeightedGraph {
+        let edges: Vec<(Vertex, Vertex, f64)> = counts
+            .iter()
+            .filter(|&&(_, _, c)| c > 0)
+            .map(|&(u, v, c)| (u, v, 1.0 / c as f64))
+            .collect();
+        Self::create_undirected(n, &edges)
+    }
+
+    // Implement dijkstra from a single source
+    // Input: self, the source vertex
+    // Output: the shortest weighted distance to every vertex (None if unreachable, and all None if source is out of range)
+    pub fn dijkstra(&self, source: Vertex) -> Vec<Option<f64>> {
+        let mut distance: Vec<Option<f64>> = vec![None; self.n];
+        if source >= self.n {
+            return distance;
+        }
+        distance[source] = Some(0.0);
+
+        let mut heap = BinaryHeap::new();
+        heap.push(State {
+            cost: 0.0,
+            vertex: source,
+        });
+
+        // Pop the closest vertex (v), and relax the edges to its neighbors (u)
+        // Skip stale entries that were already improved on
+        while let Some(State { cost, vertex: v }) = heap.pop() {
+            if distance[v].is_some_and(|d| cost > d) {
+                continue;
+            }
+            for &(u, w) in &self.outedges[v] {
+                let next = cost + w;
+                if distance[u].is_none_or(|d| next < d) {
+                    distance[u] = Some(next);
+                    heap.push(State {
+                        cost: next,
+                        vertex: u,
+                    });
+                }
+            }
+        }
+
+        distance
+    }
+
+    // Average shortest weighted distance, running dijkstra from every vertex
+    // Only finite distances between two different actors are counted
+    // Input: self, output: the average distance (0.0 if there are no connected pairs)
+    pub fn weighted_average_distance(&self) -> f64 {
+        let mut total = 0.0;
+        let mut count = 0;
+        for source in 0..self.n {
+            for (target, d) in self.dijkstra(source).iter().enumerate() {
+                if let Some(d) = d {
+                    if target != source {
+                        total += d;
+                        count += 1;
+                    }
+                }
+            }
+        }
+
+        if count > 0 {
+            total / count as f64
+        } else {
+            0.0
+        }
+    }
 }
 
 // Store actors' connections as graph
+// Actors are told apart by name alone, so two different actors with the same name become one
+// (Use connections_by with an id column to keep them apart)
 // Takes as input a dataframe, outputs a hashmap containing an actor and their collaborators
 pub fn connections(data: DataFrame) -> HashMap<ColumnVal, Vec<String>> {
-    // Find the indices containing actors
-    // In the case of imdb_top_1000.csv, where the label contains "star"
-    let mut actor_indices = vec![];
-    for (i, label) in data.labels.iter().enumerate() {
-        if label.contains("Star") {
-            actor_indices.push(i);
-        }
+    connections_by(data, |row, i| row[i].to_string())
+}
+
+// Same as connections, but each actor is stored under key(row, i) instead of their name,
+// where i is the index of the Star column they're in (e.g. their name plus an id from another column)
+// Takes as input a dataframe and the key function, outputs a hashmap containing an actor's key and their collaborators' keys
+pub fn connections_by<F: Fn(&[ColumnVal], usize) -> String>(
+    data: DataFrame,
+    key: F,
+) -> HashMap<ColumnVal, Vec<String>> {
+    collaborator_sets(&data, &star_columns(&data), key)
+}
+
+// Same as connections, but the actors are in the named columns instead of the ones with "Star" in their label
+// (For datasets with headers like actor1,actor2,actor3; names that aren't columns are ignored)
+// Takes as input a dataframe and the actor column labels
+// Outputs a result containing a hashmap of each actor and their collaborators, and a boxed dynamic error (if none of the columns exist)
+pub fn connections_from(
+    data: DataFrame,
+    actor_columns: &[&str],
+) -> Result<HashMap<ColumnVal, Vec<String>>, Box<dyn Error>> {
+    let actor_indices: Vec<usize> = data
+        .labels
+        .iter()
+        .enumerate()
+        .filter(|(_, label)| actor_columns.contains(&label.as_str()))
+        .map(|(i, _)| i)
+        .collect();
+    if actor_indices.is_empty() {
+        return Err(Box::new(MyError(format!(
+            "None of the actor columns {} were found",
+            actor_columns.join(", ")
+        ))));
     }
 
-    // Create an empty hashmap
-    let mut actors_hash: HashMap<ColumnVal, Vec<String>> = HashMap::new();
+    Ok(collaborator_sets(&data, &actor_indices, |row, i| {
+        row[i].to_string()
+    }))
+}
+
+// The collaborations behind connections, with each actor stored under key(row, i)
+// Each actor's collaborators go straight into a hashset, so nobody is listed twice
+// Takes as input a dataframe, the indices of its actor columns and the key function
+// Outputs a hashmap containing an actor's key and their collaborators' keys (sorted)
+fn collaborator_sets<F: Fn(&[ColumnVal], usize) -> String>(
+    data: &DataFrame,
+    actor_indices: &[usize],
+    key: F,
+) -> HashMap<ColumnVal, Vec<String>> {
+    let mut actors_sets: HashMap<ColumnVal, HashSet<String>> = HashMap::new();
+    for_each_co_star(
+        data,
+        actor_indices,
+        key,
+        |_, _| true,
+        |actor, collaborators| {
+            actors_sets
+                .entry(ColumnVal::One(actor.clone()))
+                .or_default()
+                .extend(collaborators);
+        },
+    );
+
+    actors_sets
+        .into_iter()
+        .map(|(actor, set)| {
+            let mut collaborators: Vec<String> = set.into_iter().collect();
+            collaborators.sort();
+            (actor, collaborators)
+        })
+        .collect()
+}
 
-    // For each row in the table, get the actors in that row (based on actor_indices)
-    // For each actor in a row, set that actor's collaborators to be all of the actors in that row
-    // If the actor is not in hash_map, make them the key, and their collaborators the values
-    // If that actor is already in hash_map, add the collaborators to the values corresponding to that actor
+// Go through the actors in each film, and hand each one to visit along with their co-stars in that film
+// An actor listed in two actor columns of a film is only kept once (so nobody ends up as their own collaborator)
+// Inputs: a dataframe, the indices of its actor columns, the key function (like collaborator_sets),
+// which pairs of billing positions (positions in actor_indices) count as collaborating,
+// and the function to call with each actor's key and their co-stars' keys (every actor in a film is visited, even alone)
+// Outputs: none
+fn for_each_co_star<F, L, V>(
+    data: &DataFrame,
+    actor_indices: &[usize],
+    key: F,
+    linked: L,
+    mut visit: V,
+) where
+    F: Fn(&[ColumnVal], usize) -> String,
+    L: Fn(usize, usize) -> bool,
+    V: FnMut(&String, Vec<String>),
+{
     for row in &data.table {
-        let actors: Vec<_> = actor_indices.iter().map(|&i| &row[i]).collect();
-        for (i, actor) in actors.iter().enumerate() {
-            if let ColumnVal::One(_) = actor {
-                let mut collaborators = vec![];
-                for (j, other) in actors.iter().enumerate() {
-                    if i != j {
-                        if let ColumnVal::One(collab) = other {
-                            collaborators.push(collab.clone());
-                        }
-                    }
+        // Each actor is stored with their billing position (which actor column they're in)
+        let mut actors: Vec<(usize, String)> = vec![];
+        for (position, &i) in actor_indices.iter().enumerate() {
+            if let ColumnVal::One(_) = row[i] {
+                let actor = key(row, i);
+                if !actors.iter().any(|(_, other)| *other == actor) {
+                    actors.push((position, actor));
                 }
-                actors_hash
-                    .entry((*actor).clone())
-                    .or_default()
-                    .extend(collaborators);
             }
         }
+        for (position, actor) in &actors {
+            let collaborators: Vec<String> = actors
+                .iter()
+                .filter(|(other_position, other)| {
+                    other != actor && linked(*position, *other_position)
+                })
+                .map(|(_, other)| other.clone())
+                .collect();
+            visit(actor, collaborators);
+        }
     }
+}
 
-    // Temporarily store the collaborators as a hashset to remove duplicates, then add them back to the hashmap
-    for (key, value) in actors_hash.clone() {
-        let set: HashSet<String> = value.into_iter().collect();
-        actors_hash.insert(key, set.into_iter().collect()); // Convert back to Vec<String>
-    }
+// Find the indices of the columns containing actors
+// In the case of imdb_top_1000.csv, where the label contains "Star"
+// Input: a dataframe, output: the column indices in order
+pub fn star_columns(data: &DataFrame) -> Vec<usize> {
+    data.labels
+        .iter()
+        .enumerate()
+        .filter(|(_, label)| label.contains("Star"))
+        .map(|(i, _)| i)
+        .collect()
+}
+
+// Same as connections, but keeps the full multiset of collaborators
+// (A pair that co-starred in five films appears five times, which is what weighted analysis needs)
+// Takes as input a dataframe, outputs a hashmap containing an actor and every collaboration they had
+pub fn raw_connections(data: &DataFrame) -> HashMap<ColumnVal, Vec<String>> {
+    windowed_connections(data, None)
+}
+
+// Same as raw_connections, but with an optional billing window
+// With Some(w), two actors in a film are only linked if their Star columns are at most w positions apart
+// (e.g. with w = 1, Star1 is linked to Star2 but not Star3), and None links everyone in the film
+// Takes as input a dataframe and the window, outputs a hashmap containing an actor and every collaboration they had
+pub fn windowed_connections(
+    data: &DataFrame,
+    billing_window: Option<usize>,
+) -> HashMap<ColumnVal, Vec<String>> {
+    // Every collaboration in every film is kept, so a pair appears once for each film they were both in
+    let mut actors_hash: HashMap<ColumnVal, Vec<String>> = HashMap::new();
+    for_each_co_star(
+        data,
+        &star_columns(data),
+        |row, i| row[i].to_string(),
+        |position, other_position| {
+            billing_window.is_none_or(|w| position.abs_diff(other_position) <= w)
+        },
+        |actor, collaborators| {
+            actors_hash
+                .entry(ColumnVal::One(actor.clone()))
+                .or_default()
+                .extend(collaborators);
+        },
+    );
 
     actors_hash
 }
 
+// Count how many films each actor appears in
+// Input: a dataframe
+// Output: a hashmap containing each actor and the number of rows they're listed in a "Star" column
+pub fn film_counts(data: &DataFrame) -> HashMap<String, usize> {
+    let actor_indices = star_columns(data);
+
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for row in &data.table {
+        // An actor listed twice in the same film still only counts once
+        let actors: HashSet<&String> = actor_indices
+            .iter()
+            .filter_map(|&i| match &row[i] {
+                ColumnVal::One(actor) if !actor.trim().is_empty() => Some(actor),
+                _ => None,
+            })
+            .collect();
+        for actor in actors {
+            *counts.entry(actor.clone()).or_default() += 1;
+        }
+    }
+    counts
+}
+
+// Calculate the average number of films each listed actor appears in
+// Input: a dataframe
+// Output: the average film count (0.0 if there are no actors)
+pub fn average_films_per_actor(data: &DataFrame) -> f64 {
+    let counts = film_counts(data);
+    if counts.is_empty() {
+        return 0.0;
+    }
+    counts.values().sum::<usize>() as f64 / counts.len() as f64
+}
+
+// Count how many films each pair of actors shared
+// Input: a dataframe
+// Output: a hashmap containing each actor, and a hashmap of their collaborators and the number of films they shared
+pub fn weighted_connections(data: &DataFrame) -> HashMap<ColumnVal, HashMap<String, u32>> {
+    raw_connections(data)
+        .into_iter()
+        .map(|(actor, collaborators)| {
+            let mut counts: HashMap<String, u32> = HashMap::new();
+            for collaborator in collaborators {
+                *counts.entry(collaborator).or_default() += 1;
+            }
+            (actor, counts)
+        })
+        .collect()
+}
+
+// Find the films that have no actors listed
+// (connections() gets nothing out of these rows, but they still count toward other stats)
+// Input: a dataframe
+// Output: the indices of the rows where every "Star" column is empty
+pub fn rows_missing_actors(data: &DataFrame) -> Vec<usize> {
+    let actor_indices = star_columns(data);
+
+    data.table
+        .iter()
+        .enumerate()
+        .filter(|(_, row)| {
+            actor_indices
+                .iter()
+                .all(|&i| row[i].to_string().trim().is_empty())
+        })
+        .map(|(r, _)| r)
+        .collect()
+}
+
 // Turn the values in a hashmap into a graph
+// Each distinct key is one vertex, so actors that share a name are only kept apart if the hash was built with connections_by
 // Input: a hashmap (designed for actors_hash)
 // Output: a graph
 pub fn hash_graph(hash: HashMap<ColumnVal, Vec<String>>) -> Graph {
+    hash_graph_with_index(hash).0
+}
+
+// Same as hash_graph, but also return which index each actor was given
+// Input: a hashmap (designed for actors_hash)
+// Output: a graph, and a hashmap from actor name to vertex
+pub fn hash_graph_with_index(
+    hash: HashMap<ColumnVal, Vec<String>>,
+) -> (Graph, HashMap<String, usize>) {
     let mut connections: ListOfEdges = vec![]; // To store actor's connections as indices
     let mut actor_to_index: HashMap<String, usize> = HashMap::new();
     let mut index = 0;
@@ -212,7 +1558,175 @@ pub fn hash_graph(hash: HashMap<ColumnVal, Vec<String>>) -> Graph {
     }
 
     // Create an undirected graph with size of the hashmap's length, and edges as connections
-    let actors_graph = Graph::create_undirected(actor_to_index.len(), &connections);
+    // Then keep each actor's name on their vertex
+    let mut actors_graph = Graph::create_undirected(actor_to_index.len(), &connections);
+    actors_graph.labels = vec![String::new(); actors_graph.n];
+    for (actor, &i) in &actor_to_index {
+        actors_graph.labels[i] = actor.clone();
+    }
+    (actors_graph, actor_to_index)
+}
+
+// Same as hash_graph_with_index, but the edges are weighted by how many films each pair of actors shared
+// (The cost of an edge is 1/shared_films, see WeightedGraph::from_shared_films)
+// Input: the shared film counts from weighted_connections
+// Output: a weighted graph, and a hashmap from actor name to vertex
+pub fn shared_films_graph(
+    weights: &HashMap<ColumnVal, HashMap<String, u32>>,
+) -> (WeightedGraph, HashMap<String, usize>) {
+    let hash: HashMap<ColumnVal, Vec<String>> = weights
+        .iter()
+        .map(|(actor, counts)| (actor.clone(), counts.keys().cloned().collect()))
+        .collect();
+    let (_, actor_to_index) = hash_graph_with_index(hash);
+
+    // Each pair is listed under both actors, so only keep it once (from its smaller vertex)
+    let mut counts: Vec<(Vertex, Vertex, u32)> = vec![];
+    for (actor, collaborators) in weights {
+        let v = actor_to_index[&actor.to_string()];
+        for (collaborator, &shared) in collaborators {
+            if let Some(&u) = actor_to_index.get(collaborator) {
+                if v < u {
+                    counts.push((v, u, shared));
+                }
+            }
+        }
+    }
+    (
+        WeightedGraph::from_shared_films(actor_to_index.len(), &counts),
+        actor_to_index,
+    )
+}
+
+// Calculate the average number of connections between an arbitrary set of actors
+// Inputs: the full graph, the map from actor name to vertex, and the names of the actors
+// Output: the bfs average over the subgraph induced by those actors, or None if fewer than two of them are in the graph
+pub fn average_connections_for(
+    graph: &Graph,
+    index_map: &HashMap<String, usize>,
+    actors: &[String],
+) -> Option<f64> {
+    let mut vertices: Vec<Vertex> = actors
+        .iter()
+        .filter_map(|actor| index_map.get(actor.trim()).copied())
+        .collect();
+    vertices.sort();
+    vertices.dedup();
+    if vertices.len() < 2 {
+        return None;
+    }
+
+    Some(graph.induced_subgraph(&vertices).bfs().1)
+}
+
+// Find every actor exactly k collaborations away from an actor, with one shortest path to each
+// Inputs: the graph, the map from actor name to vertex, the actor's name, and k
+// Output: one path per actor at distance k, as actor names from the root to that actor (empty if the root isn't in the graph)
+pub fn paths_at_distance(
+    graph: &Graph,
+    index_map: &HashMap<String, usize>,
+    name: &str,
+    k: u32,
+) -> Vec<Vec<String>> {
+    let root = match index_map.get(name) {
+        Some(&root) => root,
+        None => return vec![],
+    };
+
+    // Bfs from the root, remembering which vertex each vertex was first reached from
+    let mut distance: Vec<Option<u32>> = vec![None; graph.n];
+    let mut parent: Vec<Option<Vertex>> = vec![None; graph.n];
+    distance[root] = Some(0);
+    let mut queue: VecDeque<Vertex> = VecDeque::new();
+    queue.push_back(root);
+    while let Some(v) = queue.pop_front() {
+        // Nothing past distance k is needed
+        if distance[v] == Some(k) {
+            continue;
+        }
+        for &u in &graph.outedges[v] {
+            if distance[u].is_none() {
+                distance[u] = Some(distance[v].unwrap() + 1);
+                parent[u] = Some(v);
+                queue.push_back(u);
+            }
+        }
+    }
+
+    // Walk back from each vertex at distance k to the root
+    let mut paths = vec![];
+    for target in (0..graph.n).filter(|&v| distance[v] == Some(k)) {
+        let mut path = vec![graph.label(target)];
+        let mut current = target;
+        while let Some(previous) = parent[current] {
+            path.push(graph.label(previous));
+            current = previous;
+        }
+        path.reverse();
+        paths.push(path);
+    }
+    paths.sort();
+    paths
+}
+
+// The distance from an actor to each of a set of anchor actors, so actors can be compared by their profiles
+// Inputs: the graph, the map from actor name to vertex, the actor's name, and the anchors' names
+// Output: the distance to each anchor, in order (None if the anchor is unreachable, or either actor isn't in the graph)
+pub fn distance_profile(
+    graph: &Graph,
+    index_map: &HashMap<String, usize>,
+    actor: &str,
+    anchors: &[String],
+) -> Vec<Option<u32>> {
+    let distance = match index_map.get(actor) {
+        Some(&v) => graph.bfs_from(v),
+        None => return vec![None; anchors.len()],
+    };
+    anchors
+        .iter()
+        .map(|anchor| index_map.get(anchor).and_then(|&a| distance[a]))
+        .collect()
+}
+
+// Find the shortest chain of collaborators linking two actors
+// Inputs: the graph, the map from actor name to vertex, and the two actors' names
+// Output: the actors' names along a shortest path from a to b (None if they aren't connected or aren't in the graph)
+pub fn nearest_link(
+    graph: &Graph,
+    index_map: &HashMap<String, usize>,
+    a: &str,
+    b: &str,
+) -> Option<Vec<String>> {
+    let path = graph.shortest_path(*index_map.get(a)?, *index_map.get(b)?)?;
+    Some(path.into_iter().map(|v| graph.label(v)).collect())
+}
+
+// Check whether two actors have ever worked together directly (distance 1), without building the graph
+// Inputs: a hashmap of actors and their collaborators (designed for actors_hash), and two actor names
+// Output: true if either actor lists the other as a collaborator
+pub fn collaborated(hash: &HashMap<ColumnVal, Vec<String>>, a: &str, b: &str) -> bool {
+    let lists = |x: &str, y: &str| {
+        hash.get(&ColumnVal::One(x.to_string()))
+            .is_some_and(|collabs| collabs.iter().any(|c| c == y))
+    };
+    lists(a, b) || lists(b, a)
+}
+
+// Count the distinct films two actors both appear in
+// Inputs: a dataframe, and two actor names
+// Output: the number of rows where both actors are listed in a "Star" column
+pub fn shared_film_count(data: &DataFrame, a: &str, b: &str) -> usize {
+    let actor_indices = star_columns(data);
 
-    return actors_graph;
+    data.table
+        .iter()
+        .filter(|row| {
+            let listed = |name: &str| {
+                actor_indices
+                    .iter()
+                    .any(|&i| matches!(&row[i], ColumnVal::One(actor) if actor == name))
+            };
+            listed(a) && listed(b)
+        })
+        .count()
 }
@@ -1,112 +1,315 @@
 mod age;
+mod analysis;
 mod csv;
 mod genre;
 mod graph;
 
-use crate::age::*;
+use crate::analysis::*;
 use crate::csv::*;
-use crate::genre::*;
 use crate::graph::*;
+use clap::Parser;
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufRead, Write};
+
+// Only used by the tests at the bottom of this file
+#[cfg(test)]
+use crate::age::*;
+#[cfg(test)]
+use crate::genre::*;
+#[cfg(test)]
 use std::collections::HashMap;
-use std::io;
 
-// Read the csvs
-// Print out the average number of connections, as well as the average for a user-inputted age-bracket and genre
-// No inputs or outputs, just print statements
-// Also I exported actors_graph as a csv, which I then plotted in google colab
-fn main() {
-    // Read the combined csv
+// Format an average number of connections, with the given number of decimal places and the right plural
+// Inputs: the value, the number of decimal places
+// Output: a string like "0.50 connections" (only an exact "1" is singular)
+fn format_connections(value: f64, precision: usize) -> String {
+    let number = format!("{:.*}", precision, value);
+    let unit = if number == "1" {
+        "connection"
+    } else {
+        "connections"
+    };
+    format!("{} {}", number, unit)
+}
+
+// Check the user's age bracket input
+// Input: the line the user typed
+// Output: the bracket if it's a whole number from 1 to 4, None otherwise
+fn parse_age_bracket(input: &str) -> Option<u32> {
+    match input.trim().parse::<u32>() {
+        Ok(bracket) if (1..=4).contains(&bracket) => Some(bracket),
+        _ => None,
+    }
+}
+
+// Ask the user for a line of input
+// Input: the question to print, output: a result containing the line they typed (empty if there's no more input)
+fn prompt(question: &str) -> io::Result<String> {
+    let mut line = String::new();
+    println!("{}", question);
+    io::stdin().read_line(&mut line)?;
+    Ok(line)
+}
+
+// Keep asking for an age bracket until the input is a number from 1 to 4
+// Input: where to read the answers from
+// Output: a result containing the bracket, or None if the input ran out first
+fn read_age_bracket(input: &mut dyn BufRead) -> io::Result<Option<u32>> {
+    loop {
+        let mut line = String::new();
+        println!("Please enter a number from 1 to 4");
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        match parse_age_bracket(&line) {
+            Some(bracket) => return Ok(Some(bracket)),
+            None => println!("Invalid input. Please enter a number between 1 and 4."),
+        }
+    }
+}
+
+// The command line arguments
+// Anything left out of the age bracket, genre and actors is asked for interactively instead
+// The csv paths can also be set with the COMBINED_CSV and TOP_CSV environment variables
+// e.g. cargo run -- --age-bracket 3 --genre comedy --actors "Tom Hanks, Meg Ryan"
+#[derive(Parser, Debug)]
+#[command(about = "Average number of connections between actors in the IMDB top 1000")]
+struct Args {
+    #[arg(
+        long,
+        value_parser = clap::value_parser!(u32).range(1..=4),
+        help = "Age bracket from 1 (youngest) to 4 (oldest)"
+    )]
+    age_bracket: Option<u32>,
+    #[arg(long, help = "Genre to average the connections in")]
+    genre: Option<String>,
+    #[arg(
+        long,
+        help = "Comma-separated list of actors to average the connections between"
+    )]
+    actors: Option<String>,
+    #[arg(long, help = "File to write the results to instead of stdout")]
+    output: Option<String>,
+    #[arg(
+        long,
+        env = "COMBINED_CSV",
+        default_value = "combined.csv",
+        help = "The csv with the actors' ages"
+    )]
+    combined: String,
+    #[arg(
+        long,
+        env = "TOP_CSV",
+        default_value = "imdb_top_1000.csv",
+        help = "The csv with the films and their stars"
+    )]
+    top: String,
+    #[arg(
+        long,
+        default_value_t = 2,
+        help = "Number of decimal places used when printing averages"
+    )]
+    precision: usize,
+}
+
+// Write the summary of the largest group of connected actors
+// Inputs: where to write to, the stats from largest_component_stats, the number of decimal places
+// Output: a result from writing
+fn write_component_stats(
+    out: &mut dyn Write,
+    stats: &ComponentStats,
+    precision: usize,
+) -> io::Result<()> {
+    writeln!(
+        out,
+        "The largest connected group has {} actors ({:.1}% of all actors) and {} collaborations",
+        stats.vertices,
+        stats.coverage * 100.0,
+        stats.edges
+    )?;
+    writeln!(
+        out,
+        "The average number of connections between actors in it is: {}, and the most is {}",
+        format_connections(stats.average, precision),
+        stats.diameter
+    )
+}
+
+// Write the age range and average number of connections for an age bracket
+// Inputs: where to write to, the analysis results, the bracket (1 to 4), the number of decimal places
+// Output: a result from writing
+fn write_bracket(
+    out: &mut dyn Write,
+    result: &AnalysisResult,
+    age_bracket: u32,
+    precision: usize,
+) -> io::Result<()> {
+    let bracket_names = ["youngest", "second youngest", "second oldest", "oldest"];
+    // There are no brackets at all when there are fewer than 4 actors with a known age
+    let bracket = match result.brackets.get(age_bracket as usize - 1) {
+        Some(bracket) => bracket,
+        None => return writeln!(out, "There is insufficient data for age brackets."),
+    };
+    match bracket.average {
+        Some(average) => writeln!(
+            out,
+            "The {} actors are between {} and {}, and have {} to each other on average.",
+            bracket_names[age_bracket as usize - 1],
+            bracket.min_age,
+            bracket.max_age,
+            format_connections(average, precision)
+        ),
+        None => writeln!(
+            out,
+            "The {} actors are between {} and {}, but there is insufficient data to average their connections.",
+            bracket_names[age_bracket as usize - 1],
+            bracket.min_age,
+            bracket.max_age
+        ),
+    }
+}
+
+// Write the average number of connections in a genre
+// Inputs: where to write to, the analysis results, the genre (lowercase), the number of decimal places
+// Output: a result from writing
+fn write_genre(
+    out: &mut dyn Write,
+    result: &AnalysisResult,
+    genre: &str,
+    precision: usize,
+) -> io::Result<()> {
+    match result.genres.get(genre) {
+        Some(Some(genre_average)) => writeln!(
+            out,
+            "Actors in the {:?} genre have {} to each other on average",
+            genre,
+            format_connections(*genre_average, precision)
+        ),
+        Some(None) => writeln!(
+            out,
+            "There is insufficient data to average the connections in the {:?} genre",
+            genre
+        ),
+        None => writeln!(out, "Genre not found."),
+    }
+}
+
+// Write the average number of connections between a list of actors
+// Inputs: where to write to, the average (None if fewer than two of the actors were found), the number of decimal places
+// Output: a result from writing
+fn write_actor_list(out: &mut dyn Write, average: Option<f64>, precision: usize) -> io::Result<()> {
+    match average {
+        Some(average) => writeln!(
+            out,
+            "These actors have {} to each other on average",
+            format_connections(average, precision)
+        ),
+        None => writeln!(out, "Fewer than two of these actors were found."),
+    }
+}
+
+// Read the ages csv (like combined.csv) and the films csv (like imdb_top_1000.csv)
+// Inputs: the paths of the two csvs
+// Output: a result containing the (ages, films) dataframes and a boxed dynamic error that says which file couldn't be read
+fn load_datasets(
+    combined_path: &str,
+    top_path: &str,
+) -> Result<(DataFrame, DataFrame), Box<dyn Error>> {
     let mut combined = DataFrame::new();
-    combined.read_csv("combined.csv", &[1, 2, 2, 1, 1]).unwrap();
+    combined
+        .read_csv(combined_path, &[1, 2, 2, 1, 1])
+        .map_err(|e| MyError(format!("Couldn't read {}: {}", combined_path, e)))?;
 
-    // Read the top_1000 csv
     let mut top_1000 = DataFrame::new();
     top_1000
-        .read_csv(
-            "imdb_top_1000.csv",
-            &[1, 1, 2, 1, 4, 1, 3, 1, 2, 1, 1, 1, 1, 1, 2, 1],
-        )
-        .unwrap();
+        .read_csv(top_path, &[1, 1, 2, 1, 4, 1, 3, 1, 2, 1, 1, 1, 1, 1, 2, 1])
+        .map_err(|e| MyError(format!("Couldn't read {}: {}", top_path, e)))?;
+
+    Ok((combined, top_1000))
+}
+
+// Read the csvs
+// Print out the average number of connections, as well as the average for an age-bracket and genre
+// (from the command line, or asked for if they weren't given)
+// The results go to stdout, or to a file if run with --output <path> (the prompts always go to stdout)
+// Also I exported actors_graph as a csv, which I then plotted in google colab
+fn main() -> Result<(), Box<dyn Error>> {
+    // Pick where the results are written
+    let args = Args::parse();
+    let mut out: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(File::create(path)?),
+        None => Box::new(io::stdout()),
+    };
 
-    // Calculate the average number of connections between all of the actors in the top_1000 csv
+    // Read the combined and top_1000 csvs
+    let (combined, top_1000) = load_datasets(&args.combined, &args.top)?;
+
+    // Build the graph of all of the actors in the top_1000 csv
     let actors_hash = connections(top_1000.clone());
-    let actors_graph = hash_graph(actors_hash.clone());
-    let average_connections = actors_graph.bfs().1;
-    println!(
-        "The average number of connections between actors is: {}",
-        average_connections
-    );
-    &actors_graph.export_to_csv("actors_graph.csv"); //Export my graph as a csv
-
-    // Use the ages_bfs function on combined and a hash_map containing all of the actors
-    // More detail in age.rs
-    let ages_bfs = ages_bfs(combined.clone(), actors_hash.clone());
-
-    // Get user to input a number from 1 to 4, stored as age_bracket
-    let mut age_bracket = String::new();
-    println!("Please enter a number from 1 to 4");
-    io::stdin()
-        .read_line(&mut age_bracket)
-        .expect("Failed to read line");
-    let age_bracket: u32 = age_bracket.trim().parse().expect("Value is not a number");
+    let (actors_graph, actors_index) = hash_graph_with_index(actors_hash.clone());
+    actors_graph.export_to_csv("actors_graph.csv")?; //Export my graph as a csv
 
-    // Print out the age_range for actors in the age bracket, as well as their average number of connections to each other
-    match age_bracket {
-        1 => {
-            println!(
-                "The youngest actors are between {} and {}, and have {} connections to each other on average.",
-                ages_bfs.8.0, ages_bfs.8.1, ages_bfs.4.1
-            );
-        }
-        2 => {
-            println!(
-                "The second youngest actors are between {} and {}, and have {} connections to each other on average.",
-                ages_bfs.9.0, ages_bfs.9.1, ages_bfs.5.1
-            );
-        }
-        3 => {
-            println!(
-                "The second oldest actors are between {} and {}, and have {} connections to each other on average.",
-                ages_bfs.10.0, ages_bfs.10.1, ages_bfs.6.1
-            );
-        }
-        4 => {
-            println!(
-                "The oldest actors are between {} and {}, and have {} connections to each other on average.",
-                ages_bfs.11.0, ages_bfs.11.1, ages_bfs.7.1
-            );
-        }
-        _ => println!("Invalid input. Please enter a number between 1 and 4."),
+    // Print how much of the network the most-connected actor can reach within 3 hops
+    if let Some(fraction) = actors_graph.hub_reach_fractions(3).last() {
+        writeln!(
+            out,
+            "{:.1}% of actors are within 3 connections of the most-connected actor",
+            fraction * 100.0
+        )?;
     }
 
-    // Use the genres_bfs function on top_1000 and a hash containing all of the actors
-    // More detail in genre.rs
-    let genres_bfs_map: HashMap<
-        String,
-        (
-            HashMap<ColumnVal, Vec<String>>,
-            Graph,
-            Vec<(usize, usize, u32)>,
-            u32,
-        ),
-    > = genres_bfs(top_1000, actors_hash.clone());
-
-    // Ask user to enter a genre and print the average number of connections in that genre
-    let mut genre = String::new();
-    println!("Please enter a genre");
-    io::stdin()
-        .read_line(&mut genre)
-        .expect("Failed to read line");
+    // Calculate the overall, age bracket and genre averages, reusing the connections built above
+    // More detail in analysis.rs
+    let result = run_analysis(top_1000, combined, actors_hash);
+
+    // Print the size and average distance of the largest connected group, rather than the truncated overall average
+    write_component_stats(
+        &mut out,
+        &actors_graph.largest_component_stats(),
+        args.precision,
+    )?;
+    out.flush()?;
+
+    // Use the age bracket from --age-bracket, or get user to input a number from 1 to 4
+    // Keep asking until the input is valid
+    let age_bracket = match args.age_bracket {
+        Some(bracket) => bracket,
+        None => match read_age_bracket(&mut io::stdin().lock())? {
+            Some(bracket) => bracket,
+            None => {
+                println!("No input given.");
+                return Ok(());
+            }
+        },
+    };
+
+    // Print out the age_range for actors in the age bracket, as well as their average number of connections to each other
+    write_bracket(&mut out, &result, age_bracket, args.precision)?;
+    out.flush()?;
+
+    // Use the genre from --genre or ask user to enter one, and print the average number of connections in that genre
+    let genre = match args.genre {
+        Some(genre) => genre,
+        None => prompt("Please enter a genre")?,
+    };
     let genre = genre.trim().to_lowercase();
-    if let Some(genre_data) = genres_bfs_map.get(&genre) {
-        let genre_average = genre_data.3;
-        println!(
-            "Actors in the {:?} genre have {:?} connections to each other on average",
-            genre, genre_average
-        );
-    } else {
-        println!("Genre not found.");
-    }
+    write_genre(&mut out, &result, &genre, args.precision)?;
+    out.flush()?;
+
+    // Use the actors from --actors or ask user for a comma-separated list, and print the average number of connections between them
+    let actor_list = match args.actors {
+        Some(actors) => actors,
+        None => prompt("Please enter a comma-separated list of actors")?,
+    };
+    let actor_list: Vec<String> = actor_list
+        .split(',')
+        .map(|a| a.trim().to_string())
+        .collect();
+    let average = average_connections_for(&actors_graph, &actors_index, &actor_list);
+    write_actor_list(&mut out, average, args.precision)?;
+    out.flush()?;
+    Ok(())
 }
 
 // Test the code on a small csv where the BFS could be calculated by hand
@@ -119,7 +322,7 @@ fn small_test() {
     let small_hash = connections(small.clone());
     let small_graph = hash_graph(small_hash.clone());
     let small_average = small_graph.bfs().1;
-    assert_eq!(small_average, 1);
+    assert!((small_average - 182.0 / 156.0).abs() < 1e-9);
 }
 
 // Confirm that the average for an arbitrarily chosen age bracket is correct
@@ -138,7 +341,7 @@ fn oldest_test() {
     combined.read_csv("combined.csv", &[1, 2, 2, 1, 1]).unwrap();
     let hash = connections(top_1000.clone());
     let result = ages_bfs(combined, hash);
-    assert_eq!(result.7 .1, 5); // Check average BFS value for oldest group
+    assert!((result[3].bfs.1 - 5.73).abs() < 0.01); // Check average BFS value for oldest group
 }
 
 // Confirm that the average for an arbitrarily chosen genre is correct
@@ -154,5 +357,2203 @@ fn comedy_test() {
     let hash = connections(df.clone());
     let genre_data = genres_bfs(df, hash);
     let comedy_data = genre_data.get("comedy").expect("No horror genre found");
-    assert_eq!(comedy_data.3, 5);
+    assert!((comedy_data.3 - 5.55).abs() < 0.01);
+}
+
+// Check direct collaboration on small.csv: alice and jill were both in "lol", frank and jill never shared a movie
+#[test]
+fn collaborated_test() {
+    let mut small = DataFrame::new();
+    small
+        .read_csv("small.csv", &[1, 1, 1, 1, 1, 1, 1, 1])
+        .unwrap();
+    let small_hash = connections(small);
+    assert!(collaborated(&small_hash, "alice", "jill"));
+    assert!(collaborated(&small_hash, "jill", "alice"));
+    assert!(!collaborated(&small_hash, "frank", "jill"));
+}
+
+// Check the dijkstra average on a small weighted graph
+// 0 and 1 shared two films (cost 0.5), 1 and 2 shared one (cost 1), so the distances are 0.5, 1 and 1.5
+#[test]
+fn weighted_average_test() {
+    let g = WeightedGraph::from_shared_films(3, &[(0, 1, 2), (1, 2, 1)]);
+    assert!((g.weighted_average_distance() - 1.0).abs() < 1e-9);
+}
+
+// Check that train_test_split keeps every row, and that the same seed gives the same split
+#[test]
+fn train_test_split_test() {
+    let mut small = DataFrame::new();
+    small
+        .read_csv("small.csv", &[1, 1, 1, 1, 1, 1, 1, 1])
+        .unwrap();
+    let (train, test) = small.train_test_split(0.5, 42);
+    assert_eq!(train.table.len() + test.table.len(), small.table.len());
+    assert_eq!(test.table.len(), 3);
+    assert_eq!(train.labels, small.labels);
+    assert_eq!(test.types, small.types);
+
+    let (train_again, test_again) = small.train_test_split(0.5, 42);
+    assert_eq!(train.table, train_again.table);
+    assert_eq!(test.table, test_again.table);
+}
+
+// alice and bob co-starred in four movies in small.csv
+// raw_connections should keep all four, connections should keep only one
+#[test]
+fn multiplicity_test() {
+    let mut small = DataFrame::new();
+    small
+        .read_csv("small.csv", &[1, 1, 1, 1, 1, 1, 1, 1])
+        .unwrap();
+    let alice = ColumnVal::One("alice".to_string());
+    let count_bob = |hash: &HashMap<ColumnVal, Vec<String>>| {
+        hash[&alice].iter().filter(|c| c.as_str() == "bob").count()
+    };
+    assert_eq!(count_bob(&raw_connections(&small)), 4);
+    assert_eq!(count_bob(&connections(small)), 1);
+}
+
+// Check the average over a chosen subset of small.csv
+// frank-alice and alice-jill are direct collaborators, frank-jill are two apart
+// Over ordered pairs, that's (1 * 4 + 2 * 2) / 6
+#[test]
+fn average_connections_for_test() {
+    let mut small = DataFrame::new();
+    small
+        .read_csv("small.csv", &[1, 1, 1, 1, 1, 1, 1, 1])
+        .unwrap();
+    let (small_graph, small_index) = hash_graph_with_index(connections(small));
+    let subset = vec!["alice".to_string(), "jill".to_string(), "frank".to_string()];
+    let average = average_connections_for(&small_graph, &small_index, &subset).unwrap();
+    assert!((average - 8.0 / 6.0).abs() < 1e-9);
+
+    let too_few = vec!["alice".to_string(), "nobody".to_string()];
+    assert_eq!(
+        average_connections_for(&small_graph, &small_index, &too_few),
+        None
+    );
+}
+
+// Check the formatting helper, including the singular case
+#[test]
+fn format_connections_test() {
+    assert_eq!(format_connections(1.0, 0), "1 connection");
+    assert_eq!(format_connections(1.0, 2), "1.00 connections");
+    assert_eq!(format_connections(0.5, 2), "0.50 connections");
+    assert_eq!(format_connections(2.345, 1), "2.3 connections");
+    assert_eq!(format_connections(0.0, 0), "0 connections");
+}
+
+// A square 0-1-3 / 0-2-3 has two shortest paths from 0 to 3
+#[test]
+fn num_shortest_paths_test() {
+    let g = Graph::create_undirected(5, &vec![(0, 1), (1, 3), (0, 2), (2, 3)]);
+    assert_eq!(g.num_shortest_paths(0, 3), 2);
+    assert_eq!(g.num_shortest_paths(0, 1), 1);
+    assert_eq!(g.num_shortest_paths(0, 0), 1);
+    assert_eq!(g.num_shortest_paths(0, 4), 0);
+    assert_eq!(g.num_shortest_paths(5, 0), 0);
+    assert_eq!(g.num_shortest_paths(0, 5), 0);
+}
+
+// Export small.csv's graph as GEXF and check that alice's node and her edge to jill are in the file
+#[test]
+fn export_to_gexf_test() {
+    let mut small = DataFrame::new();
+    small
+        .read_csv("small.csv", &[1, 1, 1, 1, 1, 1, 1, 1])
+        .unwrap();
+    let (small_graph, small_index) = hash_graph_with_index(connections(small));
+
+    let path = std::env::temp_dir().join("small_graph.gexf");
+    let path = path.to_str().unwrap();
+    small_graph.export_to_gexf(path).unwrap();
+    let gexf = std::fs::read_to_string(path).unwrap();
+
+    let alice = small_index["alice"];
+    let jill = small_index["jill"];
+    assert!(gexf.contains(&format!(r#"<node id="{}" label="alice"/>"#, alice)));
+    assert!(gexf.contains(&format!(
+        r#"source="{}" target="{}"/>"#,
+        alice.min(jill),
+        alice.max(jill)
+    )));
+    assert!(gexf.contains(r#"<graph defaultedgetype="undirected">"#));
+}
+
+// Check the mean age per genre on small fixtures (everyone has died, so the ages don't depend on the current year)
+// Drama has ann (70), ben (50), cat (60) and dan (60); horror has ann (70) and eve (40)
+#[test]
+fn genre_ages_test() {
+    let mut ages = DataFrame::new();
+    ages.read_csv("small_ages.csv", &[1, 2, 2, 1, 1]).unwrap();
+    let mut genres = DataFrame::new();
+    genres
+        .read_csv("small_genres.csv", &[1, 1, 1, 1, 1, 1])
+        .unwrap();
+    let means = genre_ages(ages, &genres);
+    assert!((means["drama"] - 60.0).abs() < 1e-9);
+    assert!((means["horror"] - 55.0).abs() < 1e-9);
+}
+
+// The third film in small_genres.csv has no actors listed
+#[test]
+fn rows_missing_actors_test() {
+    let mut genres = DataFrame::new();
+    genres
+        .read_csv("small_genres.csv", &[1, 1, 1, 1, 1, 1])
+        .unwrap();
+    assert_eq!(rows_missing_actors(&genres), vec![2]);
+}
+
+// Compare the ordered and unordered averages on small.csv
+// The 13 actors have 78 pairs, 13 are two apart and the rest collaborated directly, so the unordered average is 91 / 78
+// The ordered average counts every pair twice, 182 / 156, which is the same
+#[test]
+fn unordered_average_test() {
+    let mut small = DataFrame::new();
+    small
+        .read_csv("small.csv", &[1, 1, 1, 1, 1, 1, 1, 1])
+        .unwrap();
+    let small_graph = hash_graph(connections(small));
+    let (ordered, ordered_average) = small_graph.bfs();
+    let (unordered, unordered_average) = small_graph.bfs_unordered();
+    assert_eq!(ordered.len(), 156);
+    assert_eq!(unordered.len(), 78);
+    assert!((ordered_average - 182.0 / 156.0).abs() < 1e-9);
+    assert!((ordered_average - unordered_average).abs() < 1e-9);
+    assert!((unordered_average - 91.0 / 78.0).abs() < 1e-9);
+}
+
+// 0, 1 and 2 form a triangle, and 2 is also connected to 3 and 4 (which aren't connected to each other)
+#[test]
+fn max_clique_test() {
+    let g = Graph::create_undirected(5, &vec![(0, 1), (1, 2), (0, 2), (2, 3), (2, 4)]);
+    assert_eq!(g.max_clique_containing(0), vec![0, 1, 2]);
+    assert_eq!(g.max_clique_containing(2), vec![0, 1, 2]);
+    assert_eq!(g.max_clique_containing(3).len(), 2);
+    assert!(g.max_clique_containing(5).is_empty());
+}
+
+// On a path 0-1-2 the middle actor has degree 2 and is closer to everyone, so weighting by degree lowers the average
+// Plain: (3 + 2 + 3) / 6, weighted: (1 * 3 + 2 * 2 + 1 * 3) / (1 * 2 + 2 * 2 + 1 * 2)
+#[test]
+fn degree_weighted_average_test() {
+    let g = Graph::create_undirected(3, &vec![(0, 1), (1, 2)]);
+    let weighted = degree_weighted_average(&g);
+    assert!((weighted - 10.0 / 8.0).abs() < 1e-9);
+    assert!(weighted < 8.0 / 6.0);
+}
+
+// jill worked directly with 6 of the 13 actors in small.csv, and the other 6 are two collaborations away
+// Each distance-2 path should go jill -> one of her collaborators -> someone they worked with
+#[test]
+fn paths_at_distance_test() {
+    let mut small = DataFrame::new();
+    small
+        .read_csv("small.csv", &[1, 1, 1, 1, 1, 1, 1, 1])
+        .unwrap();
+    let small_hash = connections(small);
+    let (small_graph, small_index) = hash_graph_with_index(small_hash.clone());
+
+    let paths = paths_at_distance(&small_graph, &small_index, "jill", 2);
+    assert_eq!(paths.len(), 6);
+    for path in &paths {
+        assert_eq!(path.len(), 3);
+        assert_eq!(path[0], "jill");
+        assert!(collaborated(&small_hash, &path[0], &path[1]));
+        assert!(collaborated(&small_hash, &path[1], &path[2]));
+        assert!(!collaborated(&small_hash, &path[0], &path[2]));
+    }
+    assert!(paths.iter().any(|path| path[2] == "frank"));
+    assert!(paths_at_distance(&small_graph, &small_index, "nobody", 2).is_empty());
+}
+
+// Bin small_ages.csv's birth years into three labeled buckets
+#[test]
+fn bin_column_test() {
+    let mut ages = DataFrame::new();
+    ages.read_csv("small_ages.csv", &[1, 2, 2, 1, 1]).unwrap();
+    ages.bin_column("birthYear", &[1935.0, 1955.0], &["old", "mid", "young"])
+        .unwrap();
+    let bins: Vec<String> = ages
+        .get_column("birthYear_bin")
+        .unwrap()
+        .iter()
+        .map(|bin| bin.to_string())
+        .collect();
+    assert_eq!(bins, vec!["old", "old", "mid", "mid", "young"]);
+    assert_eq!(ages.types.len(), ages.labels.len());
+    assert_eq!(ages.coercions.len(), ages.labels.len());
+
+    // The number of labels has to be one more than the number of edges
+    assert!(ages.bin_column("birthYear", &[1935.0], &["old"]).is_err());
+    // And the column has to be numeric
+    assert!(ages
+        .bin_column("primaryName", &[1935.0], &["a", "b"])
+        .is_err());
+}
+
+// The largest component is the path 0-1-2-3, with distances 1 + 2 + 3 + 1 + 2 + 1 (the separate edge 4-5 is ignored)
+#[test]
+fn wiener_index_test() {
+    let g = Graph::create_undirected(6, &vec![(0, 1), (1, 2), (2, 3), (4, 5)]);
+    assert_eq!(g.wiener_index(), 10);
+    assert_eq!(Graph::create_undirected(0, &vec![]).wiener_index(), 0);
+}
+
+// Only whole numbers from 1 to 4 (with surrounding whitespace) are valid age brackets
+#[test]
+fn parse_age_bracket_test() {
+    assert_eq!(parse_age_bracket("1\n"), Some(1));
+    assert_eq!(parse_age_bracket("  4 "), Some(4));
+    assert_eq!(parse_age_bracket("0"), None);
+    assert_eq!(parse_age_bracket("5"), None);
+    assert_eq!(parse_age_bracket("-1"), None);
+    assert_eq!(parse_age_bracket("two"), None);
+    assert_eq!(parse_age_bracket(""), None);
+}
+
+// small.csv has 6 movies with 7 different actors each, shared between 13 actors
+#[test]
+fn average_films_per_actor_test() {
+    let mut small = DataFrame::new();
+    small
+        .read_csv("small.csv", &[1, 1, 1, 1, 1, 1, 1, 1])
+        .unwrap();
+    assert_eq!(film_counts(&small)["bob"], 6);
+    assert_eq!(film_counts(&small)["jill"], 1);
+    assert!((average_films_per_actor(&small) - 42.0 / 13.0).abs() < 1e-9);
+}
+
+// Run the analysis on the small fixtures, and check that it survives a round trip through json
+#[test]
+fn analysis_json_test() {
+    let mut films = DataFrame::new();
+    films
+        .read_csv("small_genres.csv", &[1, 1, 1, 1, 1, 1])
+        .unwrap();
+    let mut ages = DataFrame::new();
+    ages.read_csv("small_ages.csv", &[1, 2, 2, 1, 1]).unwrap();
+
+    let result = run_analysis(films.clone(), ages, connections(films));
+    assert_eq!(result.brackets.len(), 4);
+    assert!(result.genres.contains_key("horror"));
+
+    let json = result.to_json().unwrap();
+    assert!(json.contains("overall_average"));
+    assert_eq!(AnalysisResult::from_json(&json).unwrap(), result);
+}
+
+// In small_genres.csv, ann and ben collaborated in a film that is both drama and comedy, so their edge isn't unique
+// ann and eve only worked together in horror
+#[test]
+fn genre_unique_collaborations_test() {
+    let mut films = DataFrame::new();
+    films
+        .read_csv("small_genres.csv", &[1, 1, 1, 1, 1, 1])
+        .unwrap();
+    let hash = connections(films.clone());
+    let unique = genre_unique_collaborations(films, hash);
+    assert_eq!(
+        unique,
+        vec![("ann".to_string(), "eve".to_string(), "horror".to_string())]
+    );
+}
+
+// On the path 0-1-2-3, vertex 1 is on the shortest paths of (0, 2) and (0, 3)
+// There are 3 pairs not involving a given vertex, so its normalized betweenness is 2/3
+#[test]
+fn normalized_centrality_test() {
+    let g = Graph::create_undirected(4, &vec![(0, 1), (1, 2), (2, 3)]);
+    let raw = g.betweenness(false);
+    let normalized = g.betweenness(true);
+    assert!((raw[1] - 2.0).abs() < 1e-9);
+    assert!((normalized[1] - 2.0 / 3.0).abs() < 1e-9);
+    assert!(raw[0].abs() < 1e-9);
+
+    // Vertex 1 is at distances 1, 1 and 2
+    assert!((g.closeness(false)[1] - 0.25).abs() < 1e-9);
+    assert!((g.closeness(true)[1] - 0.75).abs() < 1e-9);
+    assert!((g.harmonic(false)[1] - 2.5).abs() < 1e-9);
+    assert!((g.harmonic(true)[1] - 2.5 / 3.0).abs() < 1e-9);
+}
+
+// In small_genres.csv, horror has ann and eve, comedy has ann, ben, cat and dan
+// ann is 1 away from each of the other comedy actors, eve is 1 away from ann and 2 away from the rest
+#[test]
+fn cross_genre_average_test() {
+    let mut films = DataFrame::new();
+    films
+        .read_csv("small_genres.csv", &[1, 1, 1, 1, 1, 1])
+        .unwrap();
+    let hash = connections(films.clone());
+    let average = cross_genre_average(&films, hash.clone(), "Horror", "comedy").unwrap();
+    assert!((average - 10.0 / 7.0).abs() < 1e-9);
+    assert_eq!(cross_genre_average(&films, hash, "horror", "western"), None);
+}
+
+// small_bad.csv has two years that aren't numbers and one runtime that isn't
+// The empty year is expected, so it isn't counted
+#[test]
+fn coercion_counts_test() {
+    let mut bad = DataFrame::new();
+    bad.read_csv("small_bad.csv", &[1, 2, 4]).unwrap();
+    let counts = bad.coercion_counts();
+    assert_eq!(counts.len(), 2);
+    assert_eq!(counts[&1], 2);
+    assert_eq!(counts[&2], 1);
+    assert_eq!(bad.table.len(), 4);
+}
+
+// A triangle is fully transitive, a path has no triangles at all
+#[test]
+fn transitivity_test() {
+    let triangle = Graph::create_undirected(3, &vec![(0, 1), (1, 2), (0, 2)]);
+    assert_eq!(triangle.count_triangles(), 1);
+    assert!((triangle.transitivity() - 1.0).abs() < 1e-9);
+
+    let path = Graph::create_undirected(4, &vec![(0, 1), (1, 2), (2, 3)]);
+    assert_eq!(path.count_triangles(), 0);
+    assert!(path.transitivity().abs() < 1e-9);
+}
+
+// Export small.csv's graph with weights: alice and bob were in four movies together, alice and jill in one
+#[test]
+fn export_weighted_csv_test() {
+    let mut small = DataFrame::new();
+    small
+        .read_csv("small.csv", &[1, 1, 1, 1, 1, 1, 1, 1])
+        .unwrap();
+    let weights = weighted_connections(&small);
+    let small_graph = hash_graph(connections(small));
+
+    let path = std::env::temp_dir().join("small_graph_weighted.csv");
+    let path = path.to_str().unwrap();
+    small_graph.export_weighted_csv(path, &weights).unwrap();
+    let exported = std::fs::read_to_string(path).unwrap();
+    assert!(exported.starts_with("source,target,weight\n"));
+    assert!(exported.contains("\nalice,bob,4\n"));
+    assert!(exported.contains("\njill,alice,1\n"));
+}
+
+// On the path 0-1-2-3, vertex 1 reaches 0 and 2 in one hop, and 3 as well in two
+#[test]
+fn reach_within_test() {
+    let g = Graph::create_undirected(4, &vec![(0, 1), (1, 2), (2, 3)]);
+    assert_eq!(g.reach_within(1, 0), 0);
+    assert_eq!(g.reach_within(1, 1), 2);
+    assert_eq!(g.reach_within(1, 2), 3);
+    assert_eq!(g.all_reach_within(1), vec![1, 2, 2, 1]);
+    assert_eq!(g.reach_within(4, 2), 0);
+}
+
+// small_crlf.csv has Windows line endings, a name with a trailing "\r" and names with extra spaces
+// They should all match their clean versions, so there are only three actors
+#[test]
+fn crlf_test() {
+    let mut crlf = DataFrame::new();
+    crlf.read_csv("small_crlf.csv", &[1, 1, 1, 1]).unwrap();
+    let crlf_hash = connections(crlf);
+    assert_eq!(crlf_hash.len(), 3);
+    assert!(collaborated(&crlf_hash, "alice", "jane"));
+    assert!(collaborated(&crlf_hash, "bob", "alice"));
+}
+
+// The birth years in small_ages.csv are 1920, 1930, 1940, 1950 and 1960
+#[test]
+fn aggregate_test() {
+    let mut ages = DataFrame::new();
+    ages.read_csv("small_ages.csv", &[1, 2, 2, 1, 1]).unwrap();
+    assert_eq!(ages.aggregate("birthYear", AggOp::Sum).unwrap(), 9700.0);
+    assert_eq!(ages.aggregate("birthYear", AggOp::Count).unwrap(), 5.0);
+    assert_eq!(ages.aggregate("birthYear", AggOp::Min).unwrap(), 1920.0);
+    let std = ages.aggregate("birthYear", AggOp::Std).unwrap();
+    assert!((std - 200f64.sqrt()).abs() < 1e-9);
+    assert!(ages.aggregate("primaryName", AggOp::Sum).is_err());
+}
+
+// Export the distances of the path 0-1-2 and check that 0 and 2 are two apart (a header plus 6 ordered pairs)
+#[test]
+fn export_bfs_to_csv_test() {
+    let g = Graph::create_undirected(3, &vec![(0, 1), (1, 2)]);
+    let path = std::env::temp_dir().join("path_bfs.csv");
+    let path = path.to_str().unwrap();
+    g.export_bfs_to_csv(path).unwrap();
+    let exported = std::fs::read_to_string(path).unwrap();
+    assert!(exported.starts_with("source,target,distance\n"));
+    assert!(exported.contains("\n0,2,2\n"));
+    assert_eq!(exported.lines().count(), 7);
+}
+
+// Build a small dataframe from columns and check a couple of cells
+#[test]
+fn from_columns_test() {
+    let df = DataFrame::from_columns(
+        vec!["name".to_string(), "year".to_string()],
+        vec![
+            vec![
+                ColumnVal::One("alice".to_string()),
+                ColumnVal::One("bob".to_string()),
+            ],
+            vec![ColumnVal::Two(1990), ColumnVal::Two(1985)],
+        ],
+        vec![1, 2],
+    )
+    .unwrap();
+    assert_eq!(df.table.len(), 2);
+    assert_eq!(df.table[1][0], ColumnVal::One("bob".to_string()));
+    assert_eq!(df.get_column("year").unwrap()[0], ColumnVal::Two(1990));
+
+    // Columns of different lengths are rejected
+    let uneven = DataFrame::from_columns(
+        vec!["name".to_string(), "year".to_string()],
+        vec![
+            vec![ColumnVal::One("alice".to_string())],
+            vec![ColumnVal::Two(1990), ColumnVal::Two(1985)],
+        ],
+        vec![1, 2],
+    );
+    assert!(uneven.is_err());
+}
+
+// Sorted by age, small_ages.csv is eve (40), ben (50), cat (60), dan (60) and ann (70)
+// With 5 actors each bracket has one actor, except the oldest which also gets the remainder
+#[test]
+fn bracket_boundary_names_test() {
+    let mut films = DataFrame::new();
+    films
+        .read_csv("small_genres.csv", &[1, 1, 1, 1, 1, 1])
+        .unwrap();
+    let mut ages = DataFrame::new();
+    ages.read_csv("small_ages.csv", &[1, 2, 2, 1, 1]).unwrap();
+
+    let result = run_analysis(films.clone(), ages, connections(films));
+    let names: Vec<(&str, &str)> = result
+        .brackets
+        .iter()
+        .map(|b| (b.youngest_actor.as_str(), b.oldest_actor.as_str()))
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            ("eve", "eve"),
+            ("ben", "ben"),
+            ("cat", "cat"),
+            ("dan", "ann")
+        ]
+    );
+    assert_eq!(result.brackets[3].min_age, 60);
+    assert_eq!(result.brackets[3].max_age, 70);
+}
+
+// A subgraph with no edges, or with a single actor, has no meaningful average
+#[test]
+fn subgroup_average_test() {
+    assert_eq!(
+        Graph::create_undirected(3, &vec![]).subgroup_average(),
+        None
+    );
+    assert_eq!(
+        Graph::create_undirected(1, &vec![]).subgroup_average(),
+        None
+    );
+    assert_eq!(
+        Graph::create_undirected(0, &vec![]).subgroup_average(),
+        None
+    );
+    assert!(Graph::create_undirected(2, &vec![(0, 1)])
+        .subgroup_average()
+        .is_some());
+    assert!(!Graph::create_undirected(3, &vec![]).has_subgroup_data());
+    assert!(Graph::create_undirected(3, &vec![(0, 1)]).has_subgroup_data());
+
+    // run_analysis reuses the bfs averages from ages_bfs and genres_bfs, so it must agree with subgroup_average
+    let mut films = DataFrame::new();
+    films
+        .read_csv("small_genres.csv", &[1, 1, 1, 1, 1, 1])
+        .unwrap();
+    let mut ages = DataFrame::new();
+    ages.read_csv("small_ages.csv", &[1, 2, 2, 1, 1]).unwrap();
+    let result = run_analysis(films.clone(), ages.clone(), connections(films.clone()));
+    let bracket_averages: Vec<Option<f64>> = ages_bfs(ages, connections(films.clone()))
+        .iter()
+        .map(|bracket| bracket.graph.subgroup_average())
+        .collect();
+    let result_averages: Vec<Option<f64>> = result
+        .brackets
+        .iter()
+        .map(|bracket| bracket.average)
+        .collect();
+    assert_eq!(result_averages, bracket_averages);
+    for (genre, genre_data) in genres_bfs(films.clone(), connections(films)) {
+        assert_eq!(result.genres[&genre], genre_data.1.subgroup_average());
+    }
+}
+
+// In a star with 4 leaves, the center's ratio is 1/4 and each leaf's is 4/1
+// Vertex 5 is isolated and skipped
+#[test]
+fn friendship_paradox_test() {
+    let star = Graph::create_undirected(6, &vec![(0, 1), (0, 2), (0, 3), (0, 4)]);
+    let ratio = star.friendship_paradox_ratio();
+    assert!((ratio - (0.25 + 4.0 * 4.0) / 5.0).abs() < 1e-9);
+    assert!(ratio > 1.0);
+}
+
+// A triangle and a separate edge: everyone is directly connected within their component, so both average 1
+// A lone vertex has no pairs, so it averages 0
+#[test]
+fn per_component_averages_test() {
+    let g = Graph::create_undirected(6, &vec![(0, 1), (1, 2), (0, 2), (3, 4)]);
+    let averages = g.per_component_averages();
+    assert_eq!(averages, vec![(3, 1.0), (2, 1.0), (1, 0.0)]);
+}
+
+// In small.csv, jill worked with alice directly and is two away from frank
+#[test]
+fn distance_profile_test() {
+    let mut small = DataFrame::new();
+    small
+        .read_csv("small.csv", &[1, 1, 1, 1, 1, 1, 1, 1])
+        .unwrap();
+    let (small_graph, small_index) = hash_graph_with_index(connections(small));
+    let anchors = vec![
+        "alice".to_string(),
+        "frank".to_string(),
+        "nobody".to_string(),
+    ];
+    assert_eq!(
+        distance_profile(&small_graph, &small_index, "jill", &anchors),
+        vec![Some(1), Some(2), None]
+    );
+}
+
+// small_duplicate.csv lists alice twice in the same movie
+// She should only collaborate with bob and carl, once each, and never with herself
+#[test]
+fn duplicate_star_test() {
+    let mut duplicate = DataFrame::new();
+    duplicate
+        .read_csv("small_duplicate.csv", &[1, 1, 1, 1, 1])
+        .unwrap();
+    let raw = raw_connections(&duplicate);
+    let mut alice = raw[&ColumnVal::One("alice".to_string())].clone();
+    alice.sort();
+    assert_eq!(alice, vec!["bob", "carl"]);
+    assert_eq!(raw[&ColumnVal::One("bob".to_string())].len(), 2);
+}
+
+// With a billing window of 1, small_genres.csv's first movie (ann, ben, cat, dan) only links adjacent stars
+#[test]
+fn billing_window_test() {
+    let mut one_film = DataFrame::new();
+    one_film
+        .read_csv("small_genres.csv", &[1, 1, 1, 1, 1, 1])
+        .unwrap();
+    one_film.table.truncate(1);
+    let windowed = windowed_connections(&one_film, Some(1));
+    let collaborators = |actor: &str| {
+        let mut c = windowed[&ColumnVal::One(actor.to_string())].clone();
+        c.sort();
+        c
+    };
+    assert_eq!(collaborators("ann"), vec!["ben"]);
+    assert_eq!(collaborators("ben"), vec!["ann", "cat"]);
+    assert_eq!(collaborators("cat"), vec!["ben", "dan"]);
+    assert_eq!(collaborators("dan"), vec!["cat"]);
+
+    // Without a window everyone in the film is linked
+    assert_eq!(
+        windowed_connections(&one_film, None)[&ColumnVal::One("ann".to_string())].len(),
+        3
+    );
+}
+
+// On the path 0-1-2-3-4, the radius-1 ego network of 2 is 1-2-3
+#[test]
+fn ego_subgraph_test() {
+    let g = Graph::create_undirected(5, &vec![(0, 1), (1, 2), (2, 3), (3, 4)]);
+    let (ego, index) = g.ego_subgraph(2, 1);
+    assert_eq!(ego.n, 3);
+    let mut kept: Vec<Vertex> = index.keys().copied().collect();
+    kept.sort();
+    assert_eq!(kept, vec![1, 2, 3]);
+    assert_eq!(ego.outedges[index[&2]], vec![index[&1], index[&3]]);
+    assert!(ego.outedges[index[&1]] == vec![index[&2]]);
+
+    let (empty, index) = g.ego_subgraph(5, 1);
+    assert_eq!(empty.n, 0);
+    assert!(index.is_empty());
+}
+
+// In small_ages.csv, ann and cat are actresses known for "One", ben and dan are actors known for "One",
+// and eve is an actress known for "Two"
+#[test]
+fn crosstab_test() {
+    let mut ages = DataFrame::new();
+    ages.read_csv("small_ages.csv", &[1, 2, 2, 1, 1]).unwrap();
+    let table = ages.crosstab("primaryProfession", "knownForTitle").unwrap();
+    let count = |profession: &str, title: &str| {
+        table
+            .get(&(
+                ColumnVal::One(profession.to_string()),
+                ColumnVal::One(title.to_string()),
+            ))
+            .copied()
+            .unwrap_or(0)
+    };
+    assert_eq!(count("actress", "One"), 2);
+    assert_eq!(count("actor", "One"), 2);
+    assert_eq!(count("actress", "Two"), 1);
+    assert_eq!(count("actor", "Two"), 0);
+    assert_eq!(table.len(), 3);
+
+    // Float columns can be cross-tabulated too, like small_ragged.csv's ratings against names
+    let mut ragged = DataFrame::new();
+    ragged.read_csv("small_ragged.csv", &[1, 2, 3]).unwrap();
+    let ratings = ragged.crosstab("rating", "name").unwrap();
+    assert_eq!(
+        ratings[&(ColumnVal::Three(7.5), ColumnVal::from("alice"))],
+        1
+    );
+    assert_eq!(ratings[&(ColumnVal::Three(6.0), ColumnVal::from("eve"))], 1);
+    assert_eq!(ratings.len(), 2);
+}
+
+// jill and frank never worked together in small.csv, so they're linked through one actor who worked with both
+#[test]
+fn nearest_link_test() {
+    let mut small = DataFrame::new();
+    small
+        .read_csv("small.csv", &[1, 1, 1, 1, 1, 1, 1, 1])
+        .unwrap();
+    let small_hash = connections(small);
+    let (small_graph, small_index) = hash_graph_with_index(small_hash.clone());
+
+    let link = nearest_link(&small_graph, &small_index, "jill", "frank").unwrap();
+    assert_eq!(link.len(), 3);
+    assert_eq!(link[0], "jill");
+    assert_eq!(link[2], "frank");
+    assert!(collaborated(&small_hash, "jill", &link[1]));
+    assert!(collaborated(&small_hash, &link[1], "frank"));
+    assert_eq!(
+        nearest_link(&small_graph, &small_index, "jill", "nobody"),
+        None
+    );
+}
+
+// Vertex 0 has the most collaborators (1, 2 and 3), and 4 is only reached through 3
+#[test]
+fn hub_reach_fractions_test() {
+    let g = Graph::create_undirected(5, &vec![(0, 1), (0, 2), (0, 3), (3, 4)]);
+    assert_eq!(g.hub_reach_fractions(2), vec![0.75, 1.0]);
+}
+
+// In small_genre_weights.csv, ben is in all three drama films and the others are in one each
+// ben is 1 away from everyone (3 in total over 3 pairs), the others 1 from ben and 2 from the other two (5 over 3)
+// Unweighted: (3 + 5 * 3) / 12, weighted by film count: (3 * 3 + 5 * 3) / (3 * 3 + 3 * 3)
+#[test]
+fn genres_bfs_weighted_test() {
+    let mut films = DataFrame::new();
+    films
+        .read_csv("small_genre_weights.csv", &[1, 1, 1, 1, 1])
+        .unwrap();
+    assert_eq!(genre_film_counts(&films)["drama"]["ben"], 3);
+
+    let hash = connections(films.clone());
+    let (unweighted, weighted) = genres_bfs_weighted(films, hash)["drama"].unwrap();
+    assert!((unweighted - 18.0 / 12.0).abs() < 1e-9);
+    assert!((weighted - 24.0 / 18.0).abs() < 1e-9);
+
+    // ann is the only actor in a western, so there is nothing to average rather than 0 connections
+    let films = DataFrame::from_columns(
+        vec![
+            "Genre".to_string(),
+            "Star1".to_string(),
+            "Star2".to_string(),
+        ],
+        vec![
+            vec!["Drama".into(), "Western".into()],
+            vec!["ann".into(), "ann".into()],
+            vec!["ben".into(), "".into()],
+        ],
+        vec![1, 1, 1],
+    )
+    .unwrap();
+    let averages = genres_bfs_weighted(films.clone(), connections(films));
+    assert_eq!(averages["western"], None);
+    assert_eq!(averages["drama"], Some((1.0, 1.0)));
+}
+
+// small_ragged.csv has one row that's too short, one that's too long, and one with a rating that isn't a number
+#[test]
+fn dropped_rows_test() {
+    let mut ragged = DataFrame::new();
+    ragged.read_csv("small_ragged.csv", &[1, 2, 3]).unwrap();
+    assert_eq!(ragged.table.len(), 2);
+    assert_eq!(ragged.length_mismatches, 2);
+    assert_eq!(ragged.parse_failures, 1);
+}
+
+// A cycle of 6 actors (1 to 6) who are all also connected to the hub 0
+// With the hub everyone is within 2 (60 / 42), without it the cycle averages 54 / 30
+#[test]
+fn average_without_hubs_test() {
+    let mut edges = vec![];
+    for v in 1..=6 {
+        edges.push((0, v));
+        edges.push((v, v % 6 + 1));
+    }
+    let g = Graph::create_undirected(7, &edges);
+    assert!((g.average_without_hubs(0) - 60.0 / 42.0).abs() < 1e-9);
+    assert!((g.average_without_hubs(1) - 1.8).abs() < 1e-9);
+    assert_eq!(g.average_without_hubs(7), 0.0);
+}
+
+// In small_genres.csv, ann was in the drama/comedy film and the horror film, eve only in the horror film
+#[test]
+fn pivot_actor_genre_test() {
+    let mut films = DataFrame::new();
+    films
+        .read_csv("small_genres.csv", &[1, 1, 1, 1, 1, 1])
+        .unwrap();
+    let pivot = pivot_actor_genre(&films);
+    assert_eq!(pivot.labels, vec!["actor", "comedy", "drama", "horror"]);
+    assert_eq!(pivot.table.len(), 5);
+
+    let row = |actor: &str| {
+        pivot
+            .table
+            .iter()
+            .find(|row| row[0] == ColumnVal::One(actor.to_string()))
+            .unwrap()
+            .clone()
+    };
+    assert_eq!(
+        row("ann")[1..],
+        [ColumnVal::Two(1), ColumnVal::Two(1), ColumnVal::Two(1)]
+    );
+    assert_eq!(
+        row("eve")[1..],
+        [ColumnVal::Two(0), ColumnVal::Two(0), ColumnVal::Two(1)]
+    );
+}
+
+// Everyone in small_genre_weights.csv is in drama, and ann worked with ben, who worked with cat and dan
+// So ann is 1 away from ben and 2 away from cat and dan
+#[test]
+fn genre_peer_average_test() {
+    let mut films = DataFrame::new();
+    films
+        .read_csv("small_genre_weights.csv", &[1, 1, 1, 1, 1])
+        .unwrap();
+    let hash = connections(films.clone());
+    let average = genre_peer_average(&films, hash.clone(), "ann").unwrap();
+    assert!((average - 5.0 / 3.0).abs() < 1e-9);
+    assert_eq!(genre_peer_average(&films, hash, "nobody"), None);
+}
+
+// Test that the report functions write the expected text into an in-memory buffer, and the --output flag
+#[test]
+fn write_report_test() {
+    let mut genres = std::collections::BTreeMap::new();
+    genres.insert("drama".to_string(), Some(1.5));
+    genres.insert("comedy".to_string(), None);
+    let result = AnalysisResult {
+        overall_average: 3.0,
+        brackets: vec![
+            BracketResult {
+                min_age: 20,
+                max_age: 40,
+                youngest_actor: "A".to_string(),
+                oldest_actor: "B".to_string(),
+                average: Some(2.0),
+                density: 0.5,
+            };
+            4
+        ],
+        genres,
+    };
+
+    let mut buffer: Vec<u8> = Vec::new();
+    let stats = ComponentStats {
+        vertices: 4,
+        edges: 3,
+        average: 5.0 / 3.0,
+        diameter: 3,
+        coverage: 0.5,
+    };
+    write_component_stats(&mut buffer, &stats, 2).unwrap();
+    write_bracket(&mut buffer, &result, 4, 2).unwrap();
+    write_genre(&mut buffer, &result, "drama", 1).unwrap();
+    write_genre(&mut buffer, &result, "comedy", 2).unwrap();
+    write_genre(&mut buffer, &result, "western", 2).unwrap();
+    write_actor_list(&mut buffer, None, 2).unwrap();
+    write_actor_list(&mut buffer, Some(1.0), 0).unwrap();
+    assert_eq!(
+        String::from_utf8(buffer).unwrap(),
+        "The largest connected group has 4 actors (50.0% of all actors) and 3 collaborations\n\
+         The average number of connections between actors in it is: 1.67 connections, and the most is 3\n\
+         The oldest actors are between 20 and 40, and have 2.00 connections to each other on average.\n\
+         Actors in the \"drama\" genre have 1.5 connections to each other on average\n\
+         There is insufficient data to average the connections in the \"comedy\" genre\n\
+         Genre not found.\n\
+         Fewer than two of these actors were found.\n\
+         These actors have 1 connection to each other on average\n"
+    );
+
+    let args = Args::try_parse_from(["final_project", "--output", "report.txt"]).unwrap();
+    assert_eq!(args.output, Some("report.txt".to_string()));
+    assert_eq!(
+        Args::try_parse_from(["final_project"]).unwrap().output,
+        None
+    );
+    assert!(Args::try_parse_from(["final_project", "--output"]).is_err());
+}
+
+// Test the number of films two actors share (alice and bob are both in both films of small_crlf.csv)
+#[test]
+fn shared_film_count_test() {
+    let mut crlf = DataFrame::new();
+    crlf.read_csv("small_crlf.csv", &[1, 1, 1, 1]).unwrap();
+    assert_eq!(shared_film_count(&crlf, "alice", "bob"), 2);
+    assert_eq!(shared_film_count(&crlf, "bob", "alice"), 2);
+
+    let mut small = DataFrame::new();
+    small
+        .read_csv("small.csv", &[1, 1, 1, 1, 1, 1, 1, 1])
+        .unwrap();
+    assert_eq!(shared_film_count(&small, "alice", "jill"), 1);
+    assert_eq!(shared_film_count(&small, "jill", "frank"), 0);
+    assert_eq!(shared_film_count(&small, "alice", "nobody"), 0);
+}
+
+// Test when a giant component first appears as the edges are added in a random order
+#[test]
+fn giant_component_threshold_test() {
+    // In a star any two edges already join 3 of the 5 vertices
+    let star = Graph::create_undirected(5, &vec![(0, 1), (0, 2), (0, 3), (0, 4)]);
+    for seed in 0..10 {
+        assert_eq!(star.giant_component_threshold(seed), 0.5);
+    }
+
+    // In a path it depends on the order, but the same seed always gives the same answer
+    let path = Graph::create_undirected(5, &vec![(0, 1), (1, 2), (2, 3), (3, 4)]);
+    let threshold = path.giant_component_threshold(42);
+    assert_eq!(threshold, path.giant_component_threshold(42));
+    assert_eq!(threshold, 0.75);
+
+    // Two separate triangles never have more than half the vertices in one component
+    let triangles =
+        Graph::create_undirected(6, &vec![(0, 1), (1, 2), (0, 2), (3, 4), (4, 5), (3, 5)]);
+    assert_eq!(triangles.giant_component_threshold(7), 1.0);
+
+    // An empty graph never has a giant component, and a single vertex is one from the start
+    assert_eq!(
+        Graph::create_undirected(0, &vec![]).giant_component_threshold(1),
+        1.0
+    );
+    assert_eq!(
+        Graph::create_undirected(1, &vec![]).giant_component_threshold(1),
+        0.0
+    );
+}
+
+// Test building ColumnVals with From/into
+#[test]
+fn column_val_from_test() {
+    assert_eq!(ColumnVal::from("Tom"), ColumnVal::One("Tom".to_string()));
+    assert_eq!(
+        ColumnVal::from("Tom".to_string()),
+        ColumnVal::One("Tom".to_string())
+    );
+    assert_eq!(ColumnVal::from(1990), ColumnVal::Two(1990));
+    let rating: ColumnVal = 7.5.into();
+    assert!(matches!(rating, ColumnVal::Three(val) if val == 7.5));
+
+    let row: Vec<ColumnVal> = vec!["Tom".into(), 1956i64.into()];
+    assert_eq!(
+        row,
+        vec![ColumnVal::One("Tom".to_string()), ColumnVal::Two(1956)]
+    );
+}
+
+// A triangle plus an isolated vertex has 3 of the 6 possible edges
+// In small_ages.csv, only the oldest bracket (dan and ann) has two actors, and they collaborated
+#[test]
+fn density_test() {
+    let g = Graph::create_undirected(4, &vec![(0, 1), (1, 2), (0, 2)]);
+    assert_eq!(g.density(), 0.5);
+    assert_eq!(Graph::create_undirected(1, &vec![]).density(), 0.0);
+
+    let mut films = DataFrame::new();
+    films
+        .read_csv("small_genres.csv", &[1, 1, 1, 1, 1, 1])
+        .unwrap();
+    let mut ages = DataFrame::new();
+    ages.read_csv("small_ages.csv", &[1, 2, 2, 1, 1]).unwrap();
+    let bracket_densities: Vec<f64> = ages_bfs(ages.clone(), connections(films.clone()))
+        .iter()
+        .map(|bracket| bracket.density)
+        .collect();
+    assert_eq!(bracket_densities, vec![0.0, 0.0, 0.0, 1.0]);
+    let densities: Vec<f64> = run_analysis(films.clone(), ages, connections(films))
+        .brackets
+        .iter()
+        .map(|bracket| bracket.density)
+        .collect();
+    assert_eq!(densities, vec![0.0, 0.0, 0.0, 1.0]);
+}
+
+// ann collaborates with bea (her own bracket) and cal (the next bracket), so she's the only actor spanning two brackets
+#[test]
+fn bracket_bridges_test() {
+    let mut hash: HashMap<ColumnVal, Vec<String>> = HashMap::new();
+    hash.insert("ann".into(), vec!["bea".to_string(), "cal".to_string()]);
+    hash.insert("bea".into(), vec!["ann".to_string()]);
+    hash.insert("cal".into(), vec!["ann".to_string()]);
+    hash.insert("dee".into(), vec![]);
+    let graph = hash_graph(hash);
+
+    let to_set = |names: &[&str]| names.iter().map(|n| n.to_string()).collect();
+    let brackets = vec![
+        to_set(&["ann", "bea"]),
+        to_set(&["cal", "dee"]),
+        to_set(&[]),
+        to_set(&[]),
+    ];
+    let bridges = bracket_bridges(&brackets, &graph, 2);
+    assert_eq!(
+        bridges,
+        vec![("ann".to_string(), 2), ("bea".to_string(), 1)]
+    );
+}
+
+// The path 0-1-2-3 is the larger of two components, with unordered distances 1, 2, 3, 1, 2, 1
+#[test]
+fn largest_component_stats_test() {
+    let g = Graph::create_undirected(6, &vec![(0, 1), (1, 2), (2, 3), (4, 5)]);
+    let stats = g.largest_component_stats();
+    assert_eq!(stats.vertices, 4);
+    assert_eq!(stats.edges, 3);
+    assert!((stats.average - 10.0 / 6.0).abs() < 1e-9);
+    assert_eq!(stats.diameter, 3);
+    assert!((stats.coverage - 4.0 / 6.0).abs() < 1e-9);
+
+    let empty = Graph::create_undirected(0, &vec![]).largest_component_stats();
+    assert_eq!(empty.vertices, 0);
+    assert_eq!(empty.average, 0.0);
+}
+
+// In small.csv, jill ("lol") and frank ("hi", "lmao") never shared a movie,
+// but alice, bob, alex, eli and joe were in a movie with each of them, so a shortest path has exactly one actor in between
+#[test]
+fn shortest_path_test() {
+    let mut small = DataFrame::new();
+    small
+        .read_csv("small.csv", &[1, 1, 1, 1, 1, 1, 1, 1])
+        .unwrap();
+    let (small_graph, small_index) = hash_graph_with_index(connections(small));
+    let jill = small_index["jill"];
+    let frank = small_index["frank"];
+    let alice = small_index["alice"];
+
+    let path = small_graph.shortest_path(jill, frank).unwrap();
+    assert_eq!(path.len(), 3);
+    assert_eq!((path[0], path[2]), (jill, frank));
+    let middle = ["alice", "bob", "alex", "eli", "joe"].map(|name| small_index[name]);
+    assert!(middle.contains(&path[1]));
+
+    assert_eq!(
+        small_graph.shortest_path(jill, alice),
+        Some(vec![jill, alice])
+    );
+    assert_eq!(small_graph.shortest_path(jill, jill), Some(vec![jill]));
+    assert_eq!(small_graph.shortest_path(jill, small_graph.n), None);
+
+    // 0 and 2 are in different components
+    let split = Graph::create_undirected(3, &vec![(0, 1)]);
+    assert_eq!(split.shortest_path(0, 2), None);
+}
+
+// Distances from one end of the path 0-1-2, with 3 off on its own
+#[test]
+fn bfs_from_test() {
+    let g = Graph::create_undirected(4, &vec![(0, 1), (1, 2)]);
+    assert_eq!(g.bfs_from(0), vec![Some(0), Some(1), Some(2), None]);
+    assert_eq!(g.bfs_from(3), vec![None, None, None, Some(0)]);
+    assert_eq!(g.bfs_from(4), vec![None; 4]);
+
+    // bfs is the same distances from every source, minus the distances to self
+    let from_0: Vec<(usize, usize, u32)> =
+        g.bfs().0.into_iter().filter(|&(u, _, _)| u == 0).collect();
+    assert_eq!(from_0, vec![(0, 1, 1), (0, 2, 2)]);
+}
+
+// Graphs built from actors keep each actor's name on their vertex, so exported csvs use names
+#[test]
+fn graph_labels_test() {
+    let mut crlf = DataFrame::new();
+    crlf.read_csv("small_crlf.csv", &[1, 1, 1, 1]).unwrap();
+    let (graph, index) = hash_graph_with_index(connections(crlf));
+    for (actor, &v) in &index {
+        assert_eq!(&graph.labels[v], actor);
+        assert_eq!(&graph.label(v), actor);
+    }
+
+    let pair = graph.induced_subgraph(&[index["alice"], index["jane"]]);
+    assert_eq!(pair.labels, vec!["alice", "jane"]);
+
+    let path = std::env::temp_dir().join("labeled_graph.csv");
+    let path = path.to_str().unwrap();
+    pair.export_to_csv(path).unwrap();
+    let exported = std::fs::read_to_string(path).unwrap();
+    assert_eq!(exported, "source,target\nalice,jane\njane,alice\n");
+
+    // Without labels, vertices are written as numbers
+    let unlabeled = Graph::create_undirected(2, &vec![(0, 1)]);
+    assert!(unlabeled.labels.is_empty());
+    assert_eq!(unlabeled.label(1), "1");
+}
+
+// The triangle alice-bob-jane from small_crlf.csv has 3 undirected edges, each written once
+// A graph with a one-way edge is written as directed
+#[test]
+fn export_to_graphml_test() {
+    let mut crlf = DataFrame::new();
+    crlf.read_csv("small_crlf.csv", &[1, 1, 1, 1]).unwrap();
+    let (graph, index) = hash_graph_with_index(connections(crlf));
+    assert!(graph.is_undirected());
+
+    let path = std::env::temp_dir().join("small_crlf.graphml");
+    let path = path.to_str().unwrap();
+    graph.export_to_graphml(path).unwrap();
+    let graphml = std::fs::read_to_string(path).unwrap();
+    assert!(graphml.contains(r#"edgedefault="undirected""#));
+    assert!(graphml.contains(&format!(
+        r#"<node id="n{}"><data key="label">alice</data></node>"#,
+        index["alice"]
+    )));
+    assert_eq!(graphml.matches("<node ").count(), 3);
+    assert_eq!(graphml.matches("<edge ").count(), 3);
+
+    // A directed graph counts as undirected if every edge has its reverse
+    let both_ways = Graph::create_directed(2, &vec![(0, 1), (1, 0), (1, 1)]);
+    assert!(both_ways.is_undirected());
+    let one_way = Graph::create_directed(2, &vec![(0, 1)]);
+    assert!(!one_way.is_undirected());
+    one_way.export_to_graphml(path).unwrap();
+    let graphml = std::fs::read_to_string(path).unwrap();
+    assert!(graphml.contains(r#"edgedefault="directed""#));
+    assert!(graphml.contains(r#"<node id="n1"/>"#));
+    assert!(graphml.contains(r#"<edge source="n0" target="n1"/>"#));
+}
+
+// Export a small labeled graph to DOT, then read the edges back out of the file
+#[test]
+fn export_to_dot_test() {
+    let mut hash: HashMap<ColumnVal, Vec<String>> = HashMap::new();
+    hash.insert("Tom Hanks".into(), vec!["Meg Ryan".to_string()]);
+    hash.insert(
+        "Meg Ryan".into(),
+        vec!["Tom Hanks".to_string(), "Bill_Pullman".to_string()],
+    );
+    hash.insert("Bill_Pullman".into(), vec!["Meg Ryan".to_string()]);
+    let graph = hash_graph(hash);
+
+    let path = std::env::temp_dir().join("sleepless.dot");
+    let path = path.to_str().unwrap();
+    graph.export_to_dot(path).unwrap();
+    let dot = std::fs::read_to_string(path).unwrap();
+    assert!(dot.starts_with("graph {\n"));
+    assert!(dot.contains("    Bill_Pullman;\n"));
+
+    let mut edges: Vec<(String, String)> = dot
+        .lines()
+        .filter_map(|line| line.trim().trim_end_matches(';').split_once(" -- "))
+        .map(|(a, b)| {
+            (
+                a.trim_matches('"').to_string(),
+                b.trim_matches('"').to_string(),
+            )
+        })
+        .map(|(a, b)| if a < b { (a, b) } else { (b, a) })
+        .collect();
+    edges.sort();
+    assert_eq!(
+        edges,
+        vec![
+            ("Bill_Pullman".to_string(), "Meg Ryan".to_string()),
+            ("Meg Ryan".to_string(), "Tom Hanks".to_string())
+        ]
+    );
+
+    // One-way edges make a digraph, with vertices written as numbers when there are no labels
+    Graph::create_directed(3, &vec![(0, 2)])
+        .export_to_dot(path)
+        .unwrap();
+    let dot = std::fs::read_to_string(path).unwrap();
+    assert_eq!(dot, "digraph {\n    0;\n    1;\n    2;\n    0 -> 2;\n}\n");
+}
+
+// small_edges.csv has two malformed lines (one field, and two that aren't numbers), and its largest vertex is 4 (3 has no edges)
+// A graph exported with actor names reads back with the same labels and edges
+#[test]
+fn import_from_csv_test() {
+    let g = Graph::import_from_csv("small_edges.csv").unwrap();
+    assert_eq!(g.n, 5);
+    assert_eq!(
+        g.outedges,
+        vec![vec![1], vec![0, 2], vec![1, 4], vec![], vec![2]]
+    );
+    assert!(g.labels.is_empty());
+
+    // small.csv doesn't have a source,target header
+    assert!(Graph::import_from_csv("small.csv").is_err());
+    assert!(Graph::import_labeled_from_csv("small.csv").is_err());
+    assert!(Graph::import_from_csv("missing.csv").is_err());
+
+    let mut crlf = DataFrame::new();
+    crlf.read_csv("small_crlf.csv", &[1, 1, 1, 1]).unwrap();
+    let graph = hash_graph(connections(crlf));
+    let path = std::env::temp_dir().join("small_crlf_edges.csv");
+    let path = path.to_str().unwrap();
+    graph.export_to_csv(path).unwrap();
+    let imported = Graph::import_labeled_from_csv(path).unwrap();
+    assert_eq!(imported.n, 3);
+    let mut names = imported.labels.clone();
+    names.sort();
+    assert_eq!(names, vec!["alice", "bob", "jane"]);
+    assert_eq!(imported.density(), 1.0);
+}
+
+// Everyone in small.csv is connected through bob, who was in every movie
+// The synthetic graph has a triangle, an edge and a lone vertex
+#[test]
+fn connected_components_test() {
+    let mut small = DataFrame::new();
+    small
+        .read_csv("small.csv", &[1, 1, 1, 1, 1, 1, 1, 1])
+        .unwrap();
+    let small_graph = hash_graph(connections(small));
+    let components = small_graph.connected_components();
+    assert_eq!(components.len(), 1);
+    assert_eq!(components[0].len(), 13);
+
+    let g = Graph::create_undirected(6, &vec![(0, 4), (4, 5), (0, 5), (1, 3)]);
+    assert_eq!(
+        g.connected_components(),
+        vec![vec![0, 4, 5], vec![1, 3], vec![2]]
+    );
+    assert!(Graph::create_undirected(0, &vec![])
+        .connected_components()
+        .is_empty());
+}
+
+// The path 2-4-5 is the larger of the two components, and becomes 0-1-2
+#[test]
+fn largest_component_test() {
+    let g = Graph::create_undirected(6, &vec![(0, 3), (2, 4), (4, 5)]);
+    let largest = g.largest_component();
+    assert_eq!(largest.n, 3);
+    assert_eq!(largest.outedges, vec![vec![1], vec![0, 2], vec![1]]);
+    assert!(largest.outedges.iter().flatten().all(|&v| v < largest.n));
+    assert_eq!(largest.bfs().1, 8.0 / 6.0);
+
+    assert_eq!(
+        Graph::create_undirected(0, &vec![]).largest_component().n,
+        0
+    );
+}
+
+// In small.csv, alice and bob worked with all 12 other actors, while jill was only in "lol" with 6 others
+#[test]
+fn degrees_test() {
+    let mut small = DataFrame::new();
+    small
+        .read_csv("small.csv", &[1, 1, 1, 1, 1, 1, 1, 1])
+        .unwrap();
+    let (small_graph, small_index) = hash_graph_with_index(connections(small));
+    let degrees = small_graph.degrees();
+    assert_eq!(degrees[small_index["alice"]], 12);
+    assert_eq!(degrees[small_index["bob"]], 12);
+    assert_eq!(degrees[small_index["jane"]], 7);
+    assert_eq!(degrees[small_index["jill"]], 6);
+
+    let distribution: Vec<(usize, usize)> = small_graph.degree_distribution().into_iter().collect();
+    assert_eq!(
+        distribution,
+        vec![(6, 1), (7, 1), (9, 2), (10, 2), (11, 5), (12, 2)]
+    );
+}
+
+// Everyone in a triangle has a coefficient of 1
+// Adding a pendant vertex 3 to vertex 2 leaves 1 of its 3 neighbor pairs linked, and 3 itself has only one neighbor
+#[test]
+fn clustering_coefficient_test() {
+    let triangle = Graph::create_undirected(3, &vec![(0, 1), (1, 2), (0, 2)]);
+    assert_eq!(triangle.clustering_coefficient(0), 1.0);
+    assert_eq!(triangle.average_clustering(), 1.0);
+
+    let g = Graph::create_undirected(4, &vec![(0, 1), (1, 2), (0, 2), (2, 3)]);
+    assert!((g.clustering_coefficient(2) - 1.0 / 3.0).abs() < 1e-9);
+    assert_eq!(g.clustering_coefficient(3), 0.0);
+    assert_eq!(g.clustering_coefficient(4), 0.0);
+    assert!((g.average_clustering() - (1.0 + 1.0 + 1.0 / 3.0) / 4.0).abs() < 1e-9);
+}
+
+// One triangle 1-2-3, with dangling edges 0-1, 3-4 and 4-5 that don't close any others
+// small.csv's count is checked against counting every triple by hand
+#[test]
+fn count_triangles_test() {
+    let g = Graph::create_undirected(6, &vec![(1, 2), (2, 3), (1, 3), (0, 1), (3, 4), (4, 5)]);
+    assert_eq!(g.count_triangles(), 1);
+
+    let mut small = DataFrame::new();
+    small
+        .read_csv("small.csv", &[1, 1, 1, 1, 1, 1, 1, 1])
+        .unwrap();
+    let small_graph = hash_graph(connections(small));
+    let mut brute_force = 0;
+    for a in 0..small_graph.n {
+        for b in a + 1..small_graph.n {
+            for c in b + 1..small_graph.n {
+                let linked = |x: usize, y: usize| small_graph.outedges[x].contains(&y);
+                if linked(a, b) && linked(b, c) && linked(a, c) {
+                    brute_force += 1;
+                }
+            }
+        }
+    }
+    assert_eq!(small_graph.count_triangles(), brute_force);
+}
+
+// On the path 0-1-2-3-4 the ends are 4 apart and the middle is at most 2 from anyone
+// The separate vertex 5 doesn't make anything infinitely far
+#[test]
+fn diameter_test() {
+    let g = Graph::create_undirected(6, &vec![(0, 1), (1, 2), (2, 3), (3, 4)]);
+    assert_eq!(g.eccentricity(0), Some(4));
+    assert_eq!(g.eccentricity(2), Some(2));
+    assert_eq!(g.eccentricity(5), Some(0));
+    assert_eq!(g.eccentricity(6), None);
+    assert_eq!(g.diameter(), Some(4));
+    assert_eq!(Graph::create_undirected(0, &vec![]).diameter(), None);
+}
+
+// On the triangle 0-1-2, the direct edge 0-2 costs 5, so going through 1 (2 + 2) is shorter
+// With every edge costing 1, dijkstra matches bfs_from
+#[test]
+fn weighted_dijkstra_test() {
+    let g = WeightedGraph::create_undirected(4, &[(0, 1, 2.0), (1, 2, 2.0), (0, 2, 5.0)]);
+    assert_eq!(g.dijkstra(0), vec![Some(0.0), Some(2.0), Some(4.0), None]);
+    assert_eq!(g.dijkstra(4), vec![None; 4]);
+
+    let edges = vec![(0, 1), (1, 2), (2, 3), (1, 3), (4, 5)];
+    let unweighted = Graph::create_undirected(6, &edges);
+    let unit: Vec<(usize, usize, f64)> = edges.iter().map(|&(u, v)| (u, v, 1.0)).collect();
+    let unit = WeightedGraph::create_undirected(6, &unit);
+    for v in 0..6 {
+        let bfs: Vec<Option<f64>> = unweighted
+            .bfs_from(v)
+            .into_iter()
+            .map(|d| d.map(f64::from))
+            .collect();
+        assert_eq!(unit.dijkstra(v), bfs);
+    }
+}
+
+// ann and ben shared two films (cost 0.5) and ben and cat one (cost 1), so cat is 1.5 from ann
+#[test]
+fn shared_films_graph_test() {
+    let films = DataFrame::from_columns(
+        vec![
+            "movie".to_string(),
+            "Star1".to_string(),
+            "Star2".to_string(),
+        ],
+        vec![
+            vec!["One".into(), "Two".into(), "Three".into()],
+            vec!["ann".into(), "ben".into(), "cat".into()],
+            vec!["ben".into(), "ann".into(), "ben".into()],
+        ],
+        vec![1, 1, 1],
+    )
+    .unwrap();
+    let (g, index) = shared_films_graph(&weighted_connections(&films));
+    let from_ann = g.dijkstra(index["ann"]);
+    assert_eq!(from_ann[index["ben"]], Some(0.5));
+    assert_eq!(from_ann[index["cat"]], Some(1.5));
+}
+
+// ann and cat shared one film (cost 1), but ann and ben, and ben and cat, shared three each (cost 1/3)
+// So the stronger two-hop route through ben beats the direct edge, unlike in bfs
+#[test]
+fn shared_films_two_hop_test() {
+    let films = DataFrame::from_columns(
+        vec!["Star1".to_string(), "Star2".to_string()],
+        vec![
+            vec![
+                "ann".into(),
+                "ann".into(),
+                "ann".into(),
+                "ben".into(),
+                "ben".into(),
+                "ben".into(),
+                "ann".into(),
+            ],
+            vec![
+                "ben".into(),
+                "ben".into(),
+                "ben".into(),
+                "cat".into(),
+                "cat".into(),
+                "cat".into(),
+                "cat".into(),
+            ],
+        ],
+        vec![1, 1],
+    )
+    .unwrap();
+    let (g, index) = shared_films_graph(&weighted_connections(&films));
+    let to_cat = g.dijkstra(index["ann"])[index["cat"]].unwrap();
+    assert!((to_cat - 2.0 / 3.0).abs() < 1e-9);
+
+    let (unweighted, unweighted_index) = hash_graph_with_index(connections(films));
+    assert_eq!(
+        unweighted.bfs_from(unweighted_index["ann"])[unweighted_index["cat"]],
+        Some(1)
+    );
+}
+
+// In small_ages.csv the actors sorted by age are eve (40), ben (50), cat (60), dan (60) and ann (70)
+// With 5 actors each quartile has one actor, and the oldest also gets the leftover
+#[test]
+fn age_bracket_result_test() {
+    let mut films = DataFrame::new();
+    films
+        .read_csv("small_genres.csv", &[1, 1, 1, 1, 1, 1])
+        .unwrap();
+    let mut ages = DataFrame::new();
+    ages.read_csv("small_ages.csv", &[1, 2, 2, 1, 1]).unwrap();
+    let brackets = ages_bfs(ages, connections(films));
+    assert_eq!(brackets.len(), 4);
+    assert_eq!(brackets[0].age_range, (40, 40));
+    assert_eq!(brackets[0].youngest_actor, "eve");
+
+    let oldest = &brackets[3];
+    assert_eq!(oldest.age_range, (60, 70));
+    assert_eq!(
+        (oldest.youngest_actor.as_str(), oldest.oldest_actor.as_str()),
+        ("dan", "ann")
+    );
+    assert_eq!(oldest.graph.n, 2);
+    assert_eq!(oldest.bfs.1, 1.0);
+}
+
+// Splitting small_ages.csv's 5 actors into 2 brackets gives eve and ben, then cat, dan and ann
+#[test]
+fn ages_bfs_n_test() {
+    let mut films = DataFrame::new();
+    films
+        .read_csv("small_genres.csv", &[1, 1, 1, 1, 1, 1])
+        .unwrap();
+    let hash = connections(films);
+    let mut ages = DataFrame::new();
+    ages.read_csv("small_ages.csv", &[1, 2, 2, 1, 1]).unwrap();
+
+    let halves = ages_bfs_n(ages.clone(), hash.clone(), 2);
+    let ranges: Vec<(i32, i32)> = halves.iter().map(|b| b.age_range).collect();
+    assert_eq!(ranges, vec![(40, 50), (60, 70)]);
+    assert_eq!(halves[1].graph.n, 3);
+
+    assert_eq!(ages_bfs_n(ages.clone(), hash.clone(), 5).len(), 5);
+    assert!(ages_bfs_n(ages.clone(), hash.clone(), 0).is_empty());
+    assert!(ages_bfs_n(ages, hash, 6).is_empty());
+}
+
+// small_ages.csv's ages are 40 (eve), 50 (ben), 60 (cat, dan) and 70 (ann), so ann isn't in either range
+#[test]
+fn ages_bfs_ranges_test() {
+    let mut films = DataFrame::new();
+    films
+        .read_csv("small_genres.csv", &[1, 1, 1, 1, 1, 1])
+        .unwrap();
+    let hash = connections(films);
+    let mut ages = DataFrame::new();
+    ages.read_csv("small_ages.csv", &[1, 2, 2, 1, 1]).unwrap();
+
+    let brackets = ages_bfs_ranges(ages.clone(), hash.clone(), &[(55, 65), (0, 45)]).unwrap();
+    assert_eq!(brackets.len(), 2);
+    assert_eq!(brackets[0].age_range, (60, 60));
+    assert_eq!(
+        (
+            brackets[0].youngest_actor.as_str(),
+            brackets[0].oldest_actor.as_str()
+        ),
+        ("cat", "dan")
+    );
+    assert_eq!(brackets[1].youngest_actor, "eve");
+    assert_eq!(brackets[1].graph.n, 1);
+
+    assert!(ages_bfs_ranges(ages.clone(), hash.clone(), &[(0, 50), (50, 60)]).is_err());
+    assert!(ages_bfs_ranges(ages, hash, &[(60, 30)]).is_err());
+}
+
+// An actor born in 1895 only gets an age once the cutoff is moved before their birth year
+#[test]
+fn age_with_cutoff_test() {
+    let ages = DataFrame::from_columns(
+        vec![
+            "primaryName".to_string(),
+            "birthYear".to_string(),
+            "deathYear".to_string(),
+        ],
+        vec![
+            vec!["Buster".into(), "Lillian".into()],
+            vec![1895i64.into(), 1920i64.into()],
+            vec![1966i64.into(), 1990i64.into()],
+        ],
+        vec![1, 2, 2],
+    )
+    .unwrap();
+
+    let default = age(ages.clone());
+    assert_eq!(default[0], ("Buster".to_string(), None));
+    assert_eq!(
+        default[1],
+        ("Lillian".to_string(), Some(ColumnVal::Two(70)))
+    );
+
+    let classic = age_with_cutoff(ages, 1890);
+    assert_eq!(classic[0], ("Buster".to_string(), Some(ColumnVal::Two(71))));
+    assert_eq!(
+        classic[1],
+        ("Lillian".to_string(), Some(ColumnVal::Two(70)))
+    );
+}
+
+// Someone born 1950-12-31 who died 2000-06-01 was 49, not the 50 that subtracting the years gives
+// A year on either end falls back to subtracting the years
+#[test]
+fn age_from_dates_test() {
+    let ages = DataFrame::from_columns(
+        vec![
+            "primaryName".to_string(),
+            "birthYear".to_string(),
+            "deathYear".to_string(),
+        ],
+        vec![
+            vec!["A".into(), "B".into(), "C".into(), "D".into()],
+            vec![
+                "1950-12-31".into(),
+                "1950".into(),
+                "1950-12-31".into(),
+                "2000-01-01".into(),
+            ],
+            vec!["2000-06-01".into(), "2000".into(), "2000".into(), "".into()],
+        ],
+        vec![1, 1, 1],
+    )
+    .unwrap();
+
+    let ages: Vec<Option<ColumnVal>> = age(ages).into_iter().map(|(_, a)| a).collect();
+    let today = chrono::Utc::now().date_naive();
+    let d_age = today
+        .years_since(chrono::NaiveDate::from_ymd_opt(2000, 1, 1).unwrap())
+        .unwrap() as i64;
+    assert_eq!(
+        ages,
+        vec![
+            Some(ColumnVal::Two(49)),
+            Some(ColumnVal::Two(50)),
+            Some(ColumnVal::Two(50)),
+            Some(ColumnVal::Two(d_age)),
+        ]
+    );
+}
+
+// small_dates.csv has a full birth and death date, a birth date that doesn't exist, and a death date that isn't a date
+// Like a death year that can't be read, C's unreadable death date leaves them counted as living
+#[test]
+fn date_column_test() {
+    let mut dates = DataFrame::new();
+    dates.read_csv("small_dates.csv", &[1, 5, 5]).unwrap();
+    assert_eq!(dates.table.len(), 3);
+    let births = dates.get_column("birthYear").unwrap();
+    let a_birth = chrono::NaiveDate::from_ymd_opt(1950, 12, 31).unwrap();
+    assert_eq!(births[0], ColumnVal::Four(a_birth));
+    assert_eq!(births[0].to_string(), "1950-12-31");
+    // 1941 wasn't a leap year, so B's birth date can't be read
+    assert_eq!(births[1], ColumnVal::Four(NO_DATE));
+    assert_eq!(
+        dates.get_column("deathYear").unwrap()[1],
+        ColumnVal::Four(NO_DATE)
+    );
+    assert_eq!(dates.coercion_counts()[&1], 1);
+    assert_eq!(dates.coercion_counts()[&2], 1);
+
+    // Dates sort and hash like the other variants
+    assert!(births[2] < births[0]);
+    let unique: std::collections::HashSet<ColumnVal> = births.iter().cloned().collect();
+    assert_eq!(unique.len(), 3);
+
+    let ages = age(dates);
+    assert_eq!(ages[0], ("A".to_string(), Some(ColumnVal::Two(49))));
+    assert_eq!(ages[1], ("B".to_string(), None));
+    let c_age = chrono::Utc::now()
+        .date_naive()
+        .years_since(chrono::NaiveDate::from_ymd_opt(1930, 1, 1).unwrap())
+        .unwrap() as i64;
+    assert_eq!(ages[2], ("C".to_string(), Some(ColumnVal::Two(c_age))));
+}
+
+// small_flags.csv writes its flags as 1/0, true/false and yes/no in different cases, with one empty cell and one that isn't a flag
+#[test]
+fn bool_column_test() {
+    let mut flags = DataFrame::new();
+    flags.read_csv("small_flags.csv", &[1, 6, 6]).unwrap();
+    let adult = flags.get_column("isAdult").unwrap();
+    assert_eq!(
+        adult,
+        vec![
+            ColumnVal::Five(false),
+            ColumnVal::Five(true),
+            ColumnVal::Five(false),
+            ColumnVal::Five(true)
+        ]
+    );
+    let oscar: Vec<String> = flags
+        .get_column("won_oscar")
+        .unwrap()
+        .iter()
+        .map(|flag| flag.to_string())
+        .collect();
+    assert_eq!(oscar, vec!["true", "false", "false", "false"]);
+    // Only "maybe" counts as coerced, not the empty cell
+    assert_eq!(flags.coercion_counts(), HashMap::from([(2, 1)]));
+
+    assert!(ColumnVal::Five(false) < ColumnVal::Five(true));
+    let unique: std::collections::HashSet<ColumnVal> = adult.into_iter().collect();
+    assert_eq!(unique.len(), 2);
+}
+
+// Ratings can be sorted and grouped: -0.0 and 0.0 are the same, and NaN sorts last and equals itself
+#[test]
+fn float_order_test() {
+    let mut ratings: Vec<ColumnVal> = vec![
+        8.5.into(),
+        f64::NAN.into(),
+        7.0.into(),
+        (-0.0).into(),
+        0.0.into(),
+    ];
+    ratings.sort();
+    let sorted: Vec<String> = ratings.iter().map(|r| r.to_string()).collect();
+    assert_eq!(sorted, vec!["-0", "0", "7", "8.5", "NaN"]);
+
+    assert_eq!(ColumnVal::from(0.0), ColumnVal::from(-0.0));
+    assert_eq!(ColumnVal::from(f64::NAN), ColumnVal::from(f64::NAN));
+    assert_ne!(ColumnVal::from(7.0), ColumnVal::from(7.5));
+
+    let mut counts: HashMap<ColumnVal, usize> = HashMap::new();
+    for rating in [8.5, 8.5, 7.0, -0.0, 0.0] {
+        *counts.entry(rating.into()).or_default() += 1;
+    }
+    assert_eq!(counts.len(), 3);
+    assert_eq!(counts[&ColumnVal::from(0.0)], 2);
+}
+
+// Write a frame with commas and quotes in its strings, then read it back with the same types
+#[test]
+fn write_csv_test() {
+    let df = DataFrame::from_columns(
+        vec!["title".to_string(), "year".to_string()],
+        vec![
+            vec![
+                "Crouching Tiger, Hidden Dragon".into(),
+                "The \"Godfather\"".into(),
+                "Up".into(),
+            ],
+            vec![2000i64.into(), 1972i64.into(), 2009i64.into()],
+        ],
+        vec![1, 2],
+    )
+    .unwrap();
+
+    let path = std::env::temp_dir().join("written.csv");
+    let path = path.to_str().unwrap();
+    df.write_csv(path).unwrap();
+    let written = std::fs::read_to_string(path).unwrap();
+    assert!(written.starts_with("title,year\n\"Crouching Tiger, Hidden Dragon\",2000\n"));
+
+    let mut read_back = DataFrame::new();
+    read_back.read_csv(path, &[1, 2]).unwrap();
+    assert_eq!(read_back.labels, df.labels);
+    assert_eq!(read_back.table, df.table);
+}
+
+// Keep the films in imdb_top_1000.csv released after 2000 (Released_Year is the third column)
+#[test]
+fn filter_test() {
+    let mut df = DataFrame::new();
+    df.read_csv(
+        "imdb_top_1000.csv",
+        &[1, 1, 2, 1, 4, 1, 3, 1, 2, 1, 1, 1, 1, 1, 2, 1],
+    )
+    .unwrap();
+    let recent = df.filter(|row| matches!(&row[2], ColumnVal::Two(y) if *y > 2000));
+
+    let years = df.get_column("Released_Year").unwrap();
+    let expected = years
+        .iter()
+        .filter(|y| matches!(y, ColumnVal::Two(y) if *y > 2000))
+        .count();
+    assert!(expected > 0 && expected < df.table.len());
+    assert_eq!(recent.table.len(), expected);
+    assert_eq!(recent.labels, df.labels);
+    assert_eq!(recent.types, df.types);
+    assert!(recent
+        .get_column("Released_Year")
+        .unwrap()
+        .iter()
+        .all(|y| *y > ColumnVal::Two(2000)));
+}
+
+// Pick two of small_ages.csv's columns in a different order, then ask for columns that don't exist
+#[test]
+fn select_test() {
+    let mut ages = DataFrame::new();
+    ages.read_csv("small_ages.csv", &[1, 2, 2, 1, 1]).unwrap();
+    let selected = ages.select(&["birthYear", "primaryName"]).unwrap();
+    assert_eq!(selected.labels, vec!["birthYear", "primaryName"]);
+    assert_eq!(selected.types, vec![2, 1]);
+    assert_eq!(selected.table[0], vec![ColumnVal::Two(1920), "ann".into()]);
+
+    // select composes with filter
+    let born_after_1935 = ages
+        .filter(|row| row[1] > ColumnVal::Two(1935))
+        .select(&["primaryName"])
+        .unwrap();
+    assert_eq!(born_after_1935.table.len(), 3);
+
+    let error = ages
+        .select(&["primaryName", "height", "weight"])
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "There is an error: Labels height, weight not found"
+    );
+}
+
+// small_ages.csv has 5 columns, so index 4 is the last one and 5 is out of range
+#[test]
+fn get_column_by_index_test() {
+    let mut ages = DataFrame::new();
+    ages.read_csv("small_ages.csv", &[1, 2, 2, 1, 1]).unwrap();
+    assert_eq!(
+        ages.get_column_by_index(1).unwrap(),
+        ages.get_column("birthYear").unwrap()
+    );
+    assert_eq!(ages.get_column_by_index(4).unwrap()[4], "Two".into());
+    assert!(ages.get_column_by_index(5).is_err());
+    assert!(ages.get_column("height").is_err());
+}
+
+// In small_infer.csv, rating has a float so it isn't an integer column, score is a float column with an empty cell,
+// and died is an integer column with empty cells
+#[test]
+fn read_csv_infer_test() {
+    let mut inferred = DataFrame::new();
+    inferred.read_csv_infer("small_infer.csv").unwrap();
+    assert_eq!(inferred.types, vec![1, 2, 3, 1, 2]);
+    assert_eq!(inferred.table.len(), 3);
+    assert_eq!(inferred.table[0][4], ColumnVal::Two(0));
+
+    let mut ages = DataFrame::new();
+    ages.read_csv_infer("small_ages.csv").unwrap();
+    assert_eq!(ages.types, vec![1, 2, 2, 1, 1]);
+}
+
+// small_ragged.csv has 3 columns, so giving read_csv 2 types should be an error rather than an empty frame
+#[test]
+fn read_csv_type_count_test() {
+    let mut df = DataFrame::new();
+    let err = df.read_csv("small_ragged.csv", &[1, 2]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "There is an error: expected 3 types, got 2"
+    );
+}
+
+// small_ragged.csv keeps alice and eve, and skips bob, carl and dana (rows 1 to 3)
+#[test]
+fn read_csv_report_test() {
+    let mut ragged = DataFrame::new();
+    let report = ragged
+        .read_csv_report("small_ragged.csv", &[1, 2, 3])
+        .unwrap();
+    assert_eq!(
+        report,
+        ReadReport {
+            rows_kept: 2,
+            rows_skipped: 3,
+            skipped_rows: vec![1, 2, 3],
+        }
+    );
+}
+
+// read_csv_from_reader works on an in-memory string, with no fixture file
+#[test]
+fn read_csv_from_reader_test() {
+    let mut df = DataFrame::new();
+    df.read_csv_from_reader("name,year\nalice,1990\nbob,\n".as_bytes(), &[1, 2])
+        .unwrap();
+    assert_eq!(df.labels, vec!["name", "year"]);
+    assert_eq!(
+        df.table,
+        vec![
+            vec![ColumnVal::from("alice"), ColumnVal::Two(1990)],
+            vec![ColumnVal::from("bob"), ColumnVal::Two(0)],
+        ]
+    );
+
+    let mut cursor = DataFrame::new();
+    let report = cursor
+        .read_csv_report_from_reader(std::io::Cursor::new(String::from("a\n1\nx\n")), &[3])
+        .unwrap();
+    assert_eq!(report.rows_kept, 1);
+    assert_eq!(report.skipped_rows, vec![1]);
+}
+
+// small_tabs.tsv is split by tabs, so the comma in "alice, jr." is just part of the name
+#[test]
+fn read_delimited_test() {
+    let mut tabs = DataFrame::new();
+    tabs.read_delimited("small_tabs.tsv", &[1, 2, 3], b'\t')
+        .unwrap();
+    assert_eq!(
+        tabs.table,
+        vec![
+            vec![ColumnVal::from("alice, jr."), 1990.into(), 7.5.into()],
+            vec![ColumnVal::from("bob"), 1985.into(), 6.0.into()],
+        ]
+    );
+}
+
+// small_nulls.tsv uses IMDB's \N for missing years: with the null token they're empty cells, not bad integers
+#[test]
+fn null_token_test() {
+    let mut raw = DataFrame::new();
+    raw.read_delimited("small_nulls.tsv", &[1, 2, 2], b'\t')
+        .unwrap();
+    assert_eq!(raw.coercions, vec![0, 1, 2]);
+
+    let mut nulls = DataFrame::new();
+    nulls
+        .read_delimited_with_null("small_nulls.tsv", &[1, 2, 2], b'\t', "\\N")
+        .unwrap();
+    assert_eq!(nulls.coercions, vec![0, 0, 0]);
+    assert_eq!(nulls.table[0][2], ColumnVal::Two(0));
+
+    // ann has no death year so she's still alive, and ben has no birth year so he has no age
+    let ages = age(nulls);
+    let current_year = chrono::Datelike::year(&chrono::Utc::now().date_naive()) as i64;
+    assert_eq!(
+        ages[0],
+        ("ann".to_string(), Some(ColumnVal::Two(current_year - 1920)))
+    );
+    assert_eq!(ages[1], ("ben".to_string(), None));
+}
+
+// Type 4 keeps the leading digits whatever the suffix, and only falls back to 0 when there are none
+#[test]
+fn runtime_formats_test() {
+    let mut runtimes = DataFrame::new();
+    runtimes
+        .read_csv_from_reader(
+            "runtime\n142 min\n142min\n142\n2h\nabout 90 min\n".as_bytes(),
+            &[4],
+        )
+        .unwrap();
+    let values: Vec<ColumnVal> = runtimes
+        .table
+        .into_iter()
+        .map(|row| row[0].clone())
+        .collect();
+    assert_eq!(
+        values,
+        vec![
+            ColumnVal::Two(142),
+            ColumnVal::Two(142),
+            ColumnVal::Two(142),
+            ColumnVal::Two(2),
+            ColumnVal::Two(0),
+        ]
+    );
+    assert_eq!(runtimes.coercions, vec![1]);
+}
+
+// Type 7 strips the commas and dollar sign from amounts like the Gross column, and an empty cell is 0
+#[test]
+fn amount_test() {
+    let mut gross = DataFrame::new();
+    gross
+        .read_csv_from_reader(
+            "film,Gross\na,\"28,341,469\"\nb,\" $1,000 \"\nc,\nd,\"1,2x\"\n".as_bytes(),
+            &[1, 7],
+        )
+        .unwrap();
+    let values: Vec<ColumnVal> = gross.table.into_iter().map(|row| row[1].clone()).collect();
+    assert_eq!(
+        values,
+        vec![
+            ColumnVal::Two(28341469),
+            ColumnVal::Two(1000),
+            ColumnVal::Two(0),
+            ColumnVal::Two(0),
+        ]
+    );
+    assert_eq!(gross.coercions, vec![0, 1]);
+}
+
+// Sorting the top 1000 by year: Apollo 13's year is "PG", so it's read as 0 and comes first
+// The oldest real year is 1920 (Das Cabinet des Dr. Caligari), and the newest is 2020
+#[test]
+fn sort_by_column_test() {
+    let mut top_1000 = DataFrame::new();
+    top_1000
+        .read_csv(
+            "imdb_top_1000.csv",
+            &[1, 1, 2, 1, 4, 1, 3, 1, 2, 1, 1, 1, 1, 1, 2, 1],
+        )
+        .unwrap();
+
+    top_1000.sort_by_column("Released_Year", true).unwrap();
+    assert_eq!(top_1000.table[0][1], ColumnVal::from("Apollo 13"));
+    assert_eq!(
+        top_1000.table[1][1..3],
+        [ColumnVal::from("Das Cabinet des Dr. Caligari"), 1920.into()]
+    );
+    assert_eq!(top_1000.table.last().unwrap()[2], ColumnVal::Two(2020));
+
+    top_1000.sort_by_column("Released_Year", false).unwrap();
+    assert_eq!(top_1000.table[0][2], ColumnVal::Two(2020));
+    assert_eq!(
+        top_1000.table.last().unwrap()[1],
+        ColumnVal::from("Apollo 13")
+    );
+
+    // Strings sort alphabetically
+    top_1000.sort_by_column("Series_Title", true).unwrap();
+    let titles = top_1000.get_column("Series_Title").unwrap();
+    assert!(titles.windows(2).all(|pair| pair[0] <= pair[1]));
+
+    assert!(top_1000.sort_by_column("Year", true).is_err());
+}
+
+// Grouping the top 1000 by certificate: 234 U films, 197 A films and 101 with no certificate
+#[test]
+fn group_by_test() {
+    let mut top_1000 = DataFrame::new();
+    top_1000
+        .read_csv(
+            "imdb_top_1000.csv",
+            &[1, 1, 2, 1, 4, 1, 3, 1, 2, 1, 1, 1, 1, 1, 2, 1],
+        )
+        .unwrap();
+
+    let groups = top_1000.group_by("Certificate").unwrap();
+    assert_eq!(groups.len(), 17);
+    assert_eq!(groups["U"].table.len(), 234);
+    assert_eq!(groups["A"].table.len(), 197);
+    assert_eq!(groups[""].table.len(), 101);
+    assert_eq!(groups["U"].labels, top_1000.labels);
+    assert!(groups["UA"]
+        .table
+        .iter()
+        .all(|row| row[3] == ColumnVal::from("UA")));
+    let total: usize = groups.values().map(|group| group.table.len()).sum();
+    assert_eq!(total, top_1000.table.len());
+
+    assert!(top_1000.group_by("Rating").is_err());
+}
+
+// The aggregation shortcuts on an integer column (small_ages.csv's death years) and a float one (small_ragged.csv's ratings)
+#[test]
+fn column_summary_test() {
+    let mut ages = DataFrame::new();
+    ages.read_csv("small_ages.csv", &[1, 2, 2, 1, 1]).unwrap();
+    assert_eq!(ages.mean("deathYear").unwrap(), 1996.0);
+    assert_eq!(ages.median("deathYear").unwrap(), 2000.0);
+    assert_eq!(ages.min("deathYear").unwrap(), 1980.0);
+    assert_eq!(ages.max("deathYear").unwrap(), 2010.0);
+    assert_eq!(ages.sum("deathYear").unwrap(), 9980.0);
+
+    // Two ratings, so the median is halfway between them
+    let mut ragged = DataFrame::new();
+    ragged.read_csv("small_ragged.csv", &[1, 2, 3]).unwrap();
+    assert_eq!(ragged.mean("rating").unwrap(), 6.75);
+    assert_eq!(ragged.median("rating").unwrap(), 6.75);
+    assert_eq!(ragged.min("rating").unwrap(), 6.0);
+    assert_eq!(ragged.max("rating").unwrap(), 7.5);
+    assert_eq!(ragged.sum("rating").unwrap(), 13.5);
+
+    assert!(ages.mean("primaryName").is_err());
+    assert!(ages.median("nothing").is_err());
+}
+
+// A column mixing integers, floats, text and a missing (0) integer only aggregates the 3 numbers
+#[test]
+fn aggregate_mixed_column_test() {
+    let df = DataFrame::from_columns(
+        vec!["value".to_string()],
+        vec![vec![
+            ColumnVal::Two(4),
+            ColumnVal::One("n/a".to_string()),
+            ColumnVal::Three(2.0),
+            ColumnVal::Two(0),
+            ColumnVal::Two(6),
+        ]],
+        vec![2],
+    )
+    .unwrap();
+    assert_eq!(df.mean("value").unwrap(), 4.0);
+    assert_eq!(df.min("value").unwrap(), 2.0);
+    assert_eq!(df.median("value").unwrap(), 4.0);
+    assert_eq!(df.aggregate("value", AggOp::Count).unwrap(), 3.0);
+
+    let missing = DataFrame::from_columns(
+        vec!["deathYear".to_string()],
+        vec![vec![ColumnVal::Two(0), ColumnVal::Two(0)]],
+        vec![2],
+    )
+    .unwrap();
+    assert!(missing.mean("deathYear").is_err());
+    assert_eq!(missing.sum("deathYear").unwrap(), 0.0);
+}
+
+// small.csv has 6 films with 8 columns each, and a filter that keeps nothing leaves the labels but no rows
+#[test]
+fn shape_test() {
+    let mut df = DataFrame::new();
+    assert_eq!(df.shape(), (0, 0));
+    assert!(df.is_empty());
+
+    df.read_csv("small.csv", &[1, 1, 1, 1, 1, 1, 1, 1]).unwrap();
+    assert_eq!(df.shape(), (6, 8));
+    assert!(!df.is_empty());
+
+    let none = df.filter(|_| false);
+    assert_eq!(none.shape(), (0, 8));
+    assert!(none.is_empty());
+}
+
+// Two actors can't be split into quartiles, so ages_bfs returns no brackets instead of empty ones
+// An empty dataframe (without even the age columns) gives no brackets either
+#[test]
+fn ages_bfs_too_few_actors_test() {
+    let ages = DataFrame::from_columns(
+        vec![
+            "primaryName".to_string(),
+            "birthYear".to_string(),
+            "deathYear".to_string(),
+        ],
+        vec![
+            vec!["ann".into(), "ben".into()],
+            vec![1920i64.into(), 1930i64.into()],
+            vec![1990i64.into(), 1980i64.into()],
+        ],
+        vec![1, 2, 2],
+    )
+    .unwrap();
+    let hash = HashMap::new();
+    assert!(ages_bfs(ages.clone(), hash.clone()).is_empty());
+    assert_eq!(ages_bfs_n(ages, hash.clone(), 2).len(), 2);
+    assert!(ages_bfs(DataFrame::new(), hash).is_empty());
+}
+
+// Two different actors called Chris Evans (ids 1 and 2) each made a film with someone else
+// By name they're one actor linking both films, by name and id they're two separate pairs
+#[test]
+fn connections_by_test() {
+    let films = DataFrame::from_columns(
+        vec![
+            "movie".to_string(),
+            "Star1".to_string(),
+            "Star2".to_string(),
+            "Id1".to_string(),
+            "Id2".to_string(),
+        ],
+        vec![
+            vec!["Avengers".into(), "Cellular".into()],
+            vec!["Chris Evans".into(), "Chris Evans".into()],
+            vec!["Scarlett".into(), "Kim".into()],
+            vec!["1".into(), "2".into()],
+            vec!["3".into(), "4".into()],
+        ],
+        vec![1, 1, 1, 1, 1],
+    )
+    .unwrap();
+
+    let by_name = hash_graph(connections(films.clone()));
+    assert_eq!(by_name.n, 3);
+    assert_eq!(by_name.connected_components().len(), 1);
+
+    let by_id = connections_by(films, |row, i| format!("{} ({})", row[i], row[i + 2]));
+    assert_eq!(
+        by_id[&ColumnVal::from("Chris Evans (1)")],
+        vec!["Scarlett (3)".to_string()]
+    );
+    let by_id = hash_graph(by_id);
+    assert_eq!(by_id.n, 4);
+    assert_eq!(by_id.connected_components().len(), 2);
+}
+
+// A dataset whose actor columns are called actor1 and actor2 has no "Star" columns,
+// so connections finds nobody, but connections_from links ann-ben and ben-cat
+#[test]
+fn connections_from_test() {
+    let films = DataFrame::from_columns(
+        vec![
+            "title".to_string(),
+            "actor1".to_string(),
+            "actor2".to_string(),
+        ],
+        vec![
+            vec!["One".into(), "Two".into()],
+            vec!["ann".into(), "ben".into()],
+            vec!["ben".into(), "cat".into()],
+        ],
+        vec![1, 1, 1],
+    )
+    .unwrap();
+    assert!(connections(films.clone()).is_empty());
+
+    let hash = connections_from(films.clone(), &["actor1", "actor2", "actor3"]).unwrap();
+    assert_eq!(hash.len(), 3);
+    let mut ben = hash[&ColumnVal::from("ben")].clone();
+    ben.sort();
+    assert_eq!(ben, vec!["ann", "cat"]);
+
+    assert!(connections_from(films, &["Star1", "Star2"]).is_err());
+}
+
+// ann and ben made three films together, but each is still only listed once as the other's collaborator
+#[test]
+fn dedup_collaborators_test() {
+    let films = DataFrame::from_columns(
+        vec![
+            "movie".to_string(),
+            "Star1".to_string(),
+            "Star2".to_string(),
+        ],
+        vec![
+            vec!["One".into(), "Two".into(), "Three".into(), "Four".into()],
+            vec!["ann".into(), "ben".into(), "ann".into(), "cat".into()],
+            vec!["ben".into(), "ann".into(), "ben".into(), "ann".into()],
+        ],
+        vec![1, 1, 1],
+    )
+    .unwrap();
+    assert_eq!(raw_connections(&films)[&ColumnVal::from("ben")].len(), 3);
+
+    let hash = connections(films);
+    assert_eq!(hash[&ColumnVal::from("ben")], vec!["ann"]);
+    assert_eq!(hash[&ColumnVal::from("ann")], vec!["ben", "cat"]);
+}
+
+// connections builds its hashsets directly, and should give exactly what deduplicating raw_connections does
+#[test]
+fn connections_matches_raw_test() {
+    let mut top_1000 = DataFrame::new();
+    top_1000
+        .read_csv(
+            "imdb_top_1000.csv",
+            &[1, 1, 2, 1, 4, 1, 3, 1, 2, 1, 1, 1, 1, 1, 2, 1],
+        )
+        .unwrap();
+
+    let mut expected = raw_connections(&top_1000);
+    for collaborators in expected.values_mut() {
+        collaborators.sort();
+        collaborators.dedup();
+    }
+    assert_eq!(connections(top_1000), expected);
+}
+
+// Everything main would ask for can be given on the command line instead, and the csv paths have defaults
+#[test]
+fn args_test() {
+    let args = Args::try_parse_from([
+        "final_project",
+        "--age-bracket",
+        "3",
+        "--genre",
+        "comedy",
+        "--top",
+        "small.csv",
+        "--precision",
+        "3",
+    ])
+    .unwrap();
+    assert_eq!(args.age_bracket, Some(3));
+    assert_eq!(args.genre, Some("comedy".to_string()));
+    assert_eq!(args.actors, None);
+    assert_eq!(args.combined, "combined.csv");
+    assert_eq!(args.top, "small.csv");
+    assert_eq!(args.precision, 3);
+
+    // Left out, so main asks for them
+    let args = Args::try_parse_from(["final_project"]).unwrap();
+    assert_eq!((args.age_bracket, args.genre), (None, None));
+    assert_eq!(args.top, "imdb_top_1000.csv");
+    assert_eq!(args.precision, 2);
+
+    assert!(Args::try_parse_from(["final_project", "--age-bracket", "5"]).is_err());
+    assert!(Args::try_parse_from(["final_project", "--age-bracket", "two"]).is_err());
+}
+
+// load_datasets reads both csvs, and a missing file is an error that names it instead of a panic
+#[test]
+fn load_datasets_test() {
+    let (combined, top_1000) = load_datasets("combined.csv", "imdb_top_1000.csv").unwrap();
+    assert_eq!(combined.shape().1, 5);
+    assert_eq!(top_1000.shape(), (1000, 16));
+
+    let err = load_datasets("combined.csv", "missing.csv").unwrap_err();
+    assert!(err.to_string().contains("Couldn't read missing.csv"));
+}
+
+// Bad answers are asked again instead of panicking, and running out of input gives None
+#[test]
+fn read_age_bracket_test() {
+    let mut answers = io::Cursor::new("five\n7\n\n 3 \n1\n");
+    assert_eq!(read_age_bracket(&mut answers).unwrap(), Some(3));
+    assert_eq!(read_age_bracket(&mut answers).unwrap(), Some(1));
+    assert_eq!(read_age_bracket(&mut answers).unwrap(), None);
+
+    let mut no_answer = io::Cursor::new("x\n");
+    assert_eq!(read_age_bracket(&mut no_answer).unwrap(), None);
+}
+
+// With too few actors for age brackets, write_bracket says so instead of indexing past the end
+#[test]
+fn write_bracket_no_brackets_test() {
+    let result = AnalysisResult {
+        overall_average: 0.0,
+        brackets: vec![],
+        genres: std::collections::BTreeMap::new(),
+    };
+    let mut buffer: Vec<u8> = Vec::new();
+    write_bracket(&mut buffer, &result, 1, 2).unwrap();
+    write_bracket(&mut buffer, &result, 4, 2).unwrap();
+    assert_eq!(
+        String::from_utf8(buffer).unwrap(),
+        "There is insufficient data for age brackets.\n\
+         There is insufficient data for age brackets.\n"
+    );
 }